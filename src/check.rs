@@ -0,0 +1,356 @@
+//! Validations of the target information, most of them optional and enabled by command line flags.
+
+use crate::{
+    parse::{Footnote, ParsedTargetInfoFile, TriStateBool},
+    TargetInfo,
+};
+
+/// Validates that every maintainer is either a GitHub `@handle` (optionally `@org/team`),
+/// an email address, or an explicitly free-form name wrapped in double quotes.
+/// Returns all violations, one per maintainer.
+pub fn maintainer_format_violations(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        .flat_map(|target| {
+            target.maintainers.iter().filter_map(|maintainer| {
+                check_maintainer_format(maintainer).err().map(|reason| {
+                    format!("target {}: maintainer `{maintainer}` {reason}", target.name)
+                })
+            })
+        })
+        .collect()
+}
+
+/// A rule of the target tier policy that applies to some tiers.
+struct PolicyRule {
+    tiers: &'static [u8],
+    requirement: &'static str,
+    check: fn(&TargetInfo) -> bool,
+}
+
+/// The checkable parts of the target tier policy
+/// (<https://doc.rust-lang.org/nightly/rustc/target-tier-policy.html>).
+const POLICY: &[PolicyRule] = &[
+    PolicyRule {
+        tiers: &[1, 2, 3],
+        requirement: "must have maintainers",
+        check: |target| !target.maintainers.is_empty(),
+    },
+    PolicyRule {
+        tiers: &[1],
+        requirement: "must document its testing in the `Testing` section",
+        check: |target| target.sections.iter().any(|(name, _)| name == "Testing"),
+    },
+    PolicyRule {
+        tiers: &[1],
+        requirement: "must support std",
+        check: |target| target.metadata.std == Some(true),
+    },
+    PolicyRule {
+        tiers: &[1],
+        requirement: "must have host tools",
+        check: |target| target.metadata.host_tools == Some(true),
+    },
+];
+
+/// Evaluates every target against the tier policy rules for its tier.
+/// Returns all violations, one per target and rule.
+pub fn policy_violations(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        .flat_map(|target| {
+            let tier = target.metadata.tier;
+            POLICY
+                .iter()
+                .filter(move |rule| tier.is_some_and(|tier| rule.tiers.contains(&tier)))
+                .filter(|rule| !(rule.check)(target))
+                .map(move |rule| {
+                    format!(
+                        "target {} (tier {}): {}",
+                        target.name,
+                        tier.unwrap_or_default(),
+                        rule.requirement
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Finds the tier 1 and 2 targets whose `std` or `host` is unknown, which the tables show as `?`.
+/// Returns all violations, one per target and field.
+pub fn unknown_metadata_violations(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        .filter(|target| matches!(target.metadata.tier, Some(1 | 2)))
+        .flat_map(|target| {
+            let fields = [("std", target.metadata.std), ("host", target.metadata.host_tools)];
+            fields
+                .into_iter()
+                .filter(|(_, value)| TriStateBool::from(*value) == TriStateBool::Unknown)
+                .map(move |(field, _)| {
+                    format!(
+                        "target {} (tier {}): `{field}` is unknown",
+                        target.name,
+                        target.metadata.tier.unwrap_or_default()
+                    )
+                })
+        })
+        .collect()
+}
+
+/// The names of the targets without maintainers, grouped by tier in ascending order,
+/// with the targets of unknown tier last.
+pub fn unmaintained_targets(targets: &[TargetInfo]) -> Vec<(Option<u8>, Vec<&str>)> {
+    let mut by_tier = std::collections::BTreeMap::<_, Vec<_>>::new();
+    for target in targets.iter().filter(|target| target.maintainers.is_empty()) {
+        // `None` sorts first, but the targets of unknown tier are the least important.
+        let tier = target.metadata.tier.unwrap_or(u8::MAX);
+        by_tier.entry(tier).or_default().push(target.name.as_str());
+    }
+    by_tier.into_iter().map(|(tier, names)| ((tier != u8::MAX).then_some(tier), names)).collect()
+}
+
+/// Finds the targets whose tier in the target info files disagrees with the one from rustc,
+/// one violation per target. Targets without a tier in the target info files aren't checked.
+pub fn tier_mismatches(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        // Without the information from rustc, its tier is unknown rather than missing.
+        .filter(|target| !target.rustc_unavailable)
+        .filter_map(|target| match (target.tier, target.metadata.tier) {
+            (Some(tier), None) => Some(format!(
+                "target {}: the target info files say tier {tier}, but the tier is missing \
+                 from the rustc metadata",
+                target.name
+            )),
+            (Some(tier), Some(rustc_tier)) if tier != rustc_tier => Some(format!(
+                "target {}: the target info files say tier {tier}, but rustc says tier {rustc_tier}",
+                target.name
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the aliases that are the name of a target, one violation per target and alias.
+pub fn alias_collisions(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        .flat_map(|target| {
+            target
+                .aliases
+                .iter()
+                .filter(|alias| targets.iter().any(|other| other.name == **alias))
+                .map(move |alias| {
+                    format!("target {}: alias `{alias}` is the name of a target", target.name)
+                })
+        })
+        .collect()
+}
+
+/// Finds the links to target pages in the sections, like `](./<name>.md)` or
+/// `](targets/<name>.md)`, whose target doesn't exist. One violation per link.
+pub fn broken_target_links(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        .flat_map(|target| {
+            target.sections.iter().flat_map(move |(section, content)| {
+                target_page_links(content)
+                    .filter(|name| !targets.iter().any(|other| other.name == *name))
+                    .map(move |name| {
+                        format!(
+                            "target {}, section {section}: links to unknown target `{name}`",
+                            target.name
+                        )
+                    })
+            })
+        })
+        .collect()
+}
+
+/// The names of the targets whose pages the markdown links to.
+fn target_page_links(content: &str) -> impl Iterator<Item = &str> {
+    content.split("](").skip(1).filter_map(|link| {
+        let (link, _) = link.split_once(')')?;
+        let (path, _) = link.split_once('#').unwrap_or((link, ""));
+        let path = path.strip_prefix("./").or_else(|| path.strip_prefix("targets/"))?;
+        path.strip_suffix(".md").filter(|name| !name.contains('/'))
+    })
+}
+
+/// Finds the targets that list themselves in `see_also`, one violation per target.
+pub fn see_also_self_references(targets: &[TargetInfo]) -> Vec<String> {
+    targets
+        .iter()
+        .filter(|target| target.see_also.contains(&target.name))
+        .map(|target| format!("target {} lists itself in `see_also`", target.name))
+        .collect()
+}
+
+/// Finds the cycles of at least three targets referencing each other through `see_also`.
+/// Two targets referencing each other are fine. Every cycle starts with its smallest target.
+pub fn see_also_cycles(targets: &[TargetInfo]) -> Vec<Vec<String>> {
+    fn visit<'a>(
+        targets: &'a [TargetInfo],
+        path: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        let current = path[path.len() - 1];
+        let Some(target) = targets.iter().find(|target| target.name == current) else {
+            return;
+        };
+        for next in &target.see_also {
+            if *next == path[0] {
+                if path.len() >= 3 {
+                    let mut cycle = path.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+                    cycle.push(next.clone());
+                    cycles.push(cycle);
+                }
+            } else if next.as_str() > path[0] && !path.contains(&next.as_str()) {
+                path.push(next);
+                visit(targets, path, cycles);
+                path.pop();
+            }
+        }
+    }
+
+    let mut cycles = Vec::new();
+    for target in targets {
+        visit(targets, &mut vec![target.name.as_str()], &mut cycles);
+    }
+    cycles
+}
+
+/// For every target, the metadata fields (`std` and `host`) where it differs from most other
+/// targets matched by one of its patterns. Such outliers in a family are often mistakes.
+pub fn family_outliers(
+    patterns: &[ParsedTargetInfoFile],
+    targets: &[TargetInfo],
+) -> Vec<Vec<&'static str>> {
+    type Field = (&'static str, fn(&TargetInfo) -> Option<bool>);
+    let fields: [Field; 2] =
+        [("std", |target| target.metadata.std), ("host", |target| target.metadata.host_tools)];
+
+    targets
+        .iter()
+        .map(|target| {
+            // Without the information from rustc, the fields are unknown rather than different.
+            if target.rustc_unavailable {
+                return Vec::new();
+            }
+            let families = patterns
+                .iter()
+                .filter(|pattern| glob_match::glob_match(&pattern.pattern, &target.name))
+                .map(|pattern| {
+                    targets
+                        .iter()
+                        .filter(|other| !other.rustc_unavailable)
+                        .filter(|other| glob_match::glob_match(&pattern.pattern, &other.name))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            fields
+                .iter()
+                .filter(|(_, value)| {
+                    families.iter().any(|family| {
+                        let same = family.iter().filter(|other| value(other) == value(target));
+                        same.count() * 2 < family.len()
+                    })
+                })
+                .map(|(name, _)| *name)
+                .collect()
+        })
+        .collect()
+}
+
+/// Finds the shared footnotes of the patterns that `platform-support.md` doesn't define,
+/// one violation per pattern and footnote.
+pub fn undefined_footnotes(
+    patterns: &[ParsedTargetInfoFile],
+    platform_support: &str,
+) -> Vec<String> {
+    let defined = footnote_definitions(platform_support);
+    patterns
+        .iter()
+        .flat_map(|pattern| {
+            let mut undefined = pattern
+                .footnotes
+                .values()
+                .flatten()
+                .filter_map(|footnote| match footnote {
+                    Footnote::Shared(name) => Some(name.as_str()),
+                    Footnote::Scoped { .. } => None,
+                })
+                .filter(|name| !defined.contains(name))
+                .collect::<Vec<_>>();
+            undefined.sort();
+            undefined.dedup();
+            undefined.into_iter().map(|name| {
+                format!(
+                    "target pattern `{}`: footnote `{name}` is not defined in platform-support.md",
+                    pattern.pattern
+                )
+            })
+        })
+        .collect()
+}
+
+/// The names of the footnotes defined in markdown, by a `[^name]:` at the start of a line.
+pub fn footnote_definitions(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("[^")?.split_once("]:"))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// The names of the footnotes referenced in markdown, by a `[^name]` that doesn't define it.
+pub fn footnote_references(content: &str) -> Vec<&str> {
+    content
+        .match_indices("[^")
+        .filter_map(|(start, _)| {
+            let (name, after) = content[start + 2..].split_once(']')?;
+            let is_name = !name.is_empty() && !name.contains(char::is_whitespace);
+            (is_name && !after.starts_with(':')).then_some(name)
+        })
+        .collect()
+}
+
+fn check_maintainer_format(maintainer: &str) -> Result<(), &'static str> {
+    let is_handle_part = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+
+    if let Some(handle) = maintainer.strip_prefix('@') {
+        let valid = match handle.split_once('/') {
+            Some((org, team)) => is_handle_part(org) && is_handle_part(team),
+            None => is_handle_part(handle),
+        };
+        return if valid {
+            Ok(())
+        } else {
+            Err("is not a valid GitHub handle, must be `@handle` or `@org/team`")
+        };
+    }
+
+    if maintainer.len() > 2 && maintainer.starts_with('"') && maintainer.ends_with('"') {
+        return Ok(());
+    }
+
+    if let Some((local, domain)) = maintainer.split_once('@') {
+        let valid = !local.is_empty()
+            && !maintainer.contains(char::is_whitespace)
+            && !domain.contains('@')
+            && domain.split('.').count() >= 2
+            && domain.split('.').all(|part| !part.is_empty());
+        if valid {
+            return Ok(());
+        }
+    }
+
+    Err("must be a `@handle`, an email address or a free-form name in double quotes")
+}
+
+#[cfg(test)]
+mod tests;
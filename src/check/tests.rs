@@ -0,0 +1,266 @@
+use crate::{parse::ParsedTargetInfoFile, RustcTargetMetadata, TargetInfo};
+
+#[test]
+fn maintainer_format() {
+    let accepted = [
+        "@someone",
+        "@some-one123",
+        "@rust-lang/opsem",
+        "someone@example.com",
+        "\"Someone (on vacation)\"",
+    ];
+    let rejected =
+        ["someone", "John (inactive)", "@", "@some_one", "@rust-lang/", "@a/b/c", "a@b", "@ x"];
+
+    for maintainer in accepted {
+        assert_eq!(super::check_maintainer_format(maintainer), Ok(()), "{maintainer}");
+    }
+    for maintainer in rejected {
+        assert!(super::check_maintainer_format(maintainer).is_err(), "{maintainer}");
+    }
+}
+
+#[test]
+fn maintainer_format_violations_per_target() {
+    let targets = [
+        TargetInfo {
+            name: "cat-unknown-linux-gnu".to_owned(),
+            maintainers: vec!["@cat".to_owned(), "John (inactive)".to_owned()],
+            ..Default::default()
+        },
+        TargetInfo {
+            name: "dog-unknown-linux-gnu".to_owned(),
+            maintainers: vec!["@dog".to_owned()],
+            ..Default::default()
+        },
+    ];
+
+    let violations = super::maintainer_format_violations(&targets);
+
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].starts_with("target cat-unknown-linux-gnu: maintainer `John (inactive)`"));
+}
+
+#[test]
+fn policy() {
+    let tier1 = |name: &str| TargetInfo {
+        name: name.to_owned(),
+        maintainers: vec!["@someone".to_owned()],
+        sections: vec![("Testing".to_owned(), "Tested in CI.".to_owned())],
+        metadata: RustcTargetMetadata {
+            tier: Some(1),
+            std: Some(true),
+            host_tools: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let compliant = tier1("x86_64-unknown-linux-gnu");
+    let mut non_compliant = tier1("cat-unknown-linux-gnu");
+    non_compliant.maintainers.clear();
+    non_compliant.sections.clear();
+    non_compliant.metadata.host_tools = None;
+
+    assert!(super::policy_violations(&[compliant]).is_empty());
+    assert_eq!(
+        super::policy_violations(&[non_compliant]),
+        vec![
+            "target cat-unknown-linux-gnu (tier 1): must have maintainers",
+            "target cat-unknown-linux-gnu (tier 1): must document its testing in the `Testing` section",
+            "target cat-unknown-linux-gnu (tier 1): must have host tools",
+        ]
+    );
+}
+
+#[test]
+fn see_also() {
+    let target = |name: &str, see_also: &[&str]| TargetInfo {
+        name: name.to_owned(),
+        see_also: see_also.iter().map(|name| name.to_string()).collect(),
+        ..Default::default()
+    };
+    let targets = [
+        target("a-unknown-none", &["b-unknown-none"]),
+        target("b-unknown-none", &["c-unknown-none", "a-unknown-none"]),
+        target("c-unknown-none", &["a-unknown-none"]),
+        target("d-unknown-none", &["d-unknown-none", "a-unknown-none"]),
+    ];
+
+    assert_eq!(
+        super::see_also_self_references(&targets),
+        vec!["target d-unknown-none lists itself in `see_also`"]
+    );
+    // a <-> b is fine, a -> b -> c -> a is reported once.
+    assert_eq!(
+        super::see_also_cycles(&targets),
+        vec![vec!["a-unknown-none", "b-unknown-none", "c-unknown-none", "a-unknown-none"]]
+    );
+}
+
+#[test]
+fn family_outliers() {
+    let target = |name: &str, std| TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata {
+            std: Some(std),
+            host_tools: Some(false),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let targets = [
+        target("aarch64-unknown-linux-gnu", true),
+        target("cat-unknown-linux-gnu", false),
+        target("x86_64-unknown-linux-gnu", true),
+        target("x86_64-unknown-none", false),
+    ];
+    let patterns = ["*-linux-gnu", "x86_64-*"]
+        .map(|pattern| ParsedTargetInfoFile { pattern: pattern.to_owned(), ..Default::default() });
+
+    // In `x86_64-*`, it's one against one, so neither is an outlier.
+    let no_outliers = Vec::<&str>::new();
+    assert_eq!(
+        super::family_outliers(&patterns, &targets),
+        [no_outliers.clone(), vec!["std"], no_outliers.clone(), no_outliers]
+    );
+}
+
+#[test]
+fn unknown_metadata() {
+    let target = |name: &str, tier, std| TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata {
+            tier: Some(tier),
+            std,
+            host_tools: Some(false),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let targets = [
+        target("x86_64-unknown-linux-gnu", 1, Some(true)),
+        target("cat-unknown-none", 2, None),
+        target("dog-unknown-none", 3, None),
+    ];
+
+    assert_eq!(
+        super::unknown_metadata_violations(&targets),
+        ["target cat-unknown-none (tier 2): `std` is unknown"]
+    );
+    assert!(super::unknown_metadata_violations(&targets[2..]).is_empty());
+}
+
+#[test]
+fn footnotes() {
+    let content =
+        "| x | notes [^glibc] [^musl-1.2]\n\n[^glibc]: glibc.\n[^unused]: See [^glibc].\n";
+    assert_eq!(super::footnote_definitions(content), ["glibc", "unused"]);
+    assert_eq!(super::footnote_references(content), ["glibc", "musl-1.2", "glibc"]);
+
+    let shared = |name: &str| crate::parse::Footnote::Shared(name.to_owned());
+    let pattern = ParsedTargetInfoFile {
+        pattern: "*-linux-*".to_owned(),
+        footnotes: [
+            ("x86_64-unknown-linux-gnu".to_owned(), vec![shared("glibc"), shared("musl")]),
+            ("aarch64-unknown-linux-gnu".to_owned(), vec![shared("musl")]),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        super::undefined_footnotes(&[pattern], content),
+        ["target pattern `*-linux-*`: footnote `musl` is not defined in platform-support.md"]
+    );
+}
+
+#[test]
+fn unmaintained_targets_by_tier() {
+    let target = |name: &str, tier, maintainers: &[&str]| TargetInfo {
+        name: name.to_owned(),
+        maintainers: maintainers.iter().map(|m| m.to_string()).collect(),
+        metadata: RustcTargetMetadata { tier, ..Default::default() },
+        ..Default::default()
+    };
+    let targets = [
+        target("a-unknown-none", None, &[]),
+        target("b-unknown-none", Some(3), &[]),
+        target("c-unknown-linux-gnu", Some(1), &["@c"]),
+        target("d-unknown-linux-gnu", Some(2), &[]),
+        target("e-unknown-none", Some(3), &[]),
+    ];
+    assert_eq!(
+        super::unmaintained_targets(&targets),
+        [
+            (Some(2), vec!["d-unknown-linux-gnu"]),
+            (Some(3), vec!["b-unknown-none", "e-unknown-none"]),
+            (None, vec!["a-unknown-none"]),
+        ]
+    );
+}
+
+#[test]
+fn tier_mismatches() {
+    let target = |name: &str, tier, rustc_tier, rustc_unavailable| TargetInfo {
+        name: name.to_owned(),
+        tier,
+        metadata: RustcTargetMetadata { tier: rustc_tier, ..Default::default() },
+        rustc_unavailable,
+        ..Default::default()
+    };
+    let targets = [
+        target("a-unknown-none", Some(2), Some(2), false),
+        target("b-unknown-none", Some(2), Some(3), false),
+        target("c-unknown-none", Some(1), None, false),
+        target("d-unknown-none", None, Some(3), false),
+        target("e-unknown-none", Some(1), None, true),
+    ];
+    assert_eq!(
+        super::tier_mismatches(&targets),
+        [
+            "target b-unknown-none: the target info files say tier 2, but rustc says tier 3",
+            "target c-unknown-none: the target info files say tier 1, but the tier is missing \
+             from the rustc metadata",
+        ]
+    );
+}
+
+#[test]
+fn alias_collisions() {
+    let target = |name: &str, aliases: &[&str]| TargetInfo {
+        name: name.to_owned(),
+        aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        ..Default::default()
+    };
+    let targets = [
+        target("i686-pc-windows-gnu", &["i686-windows-gnu", "x86_64-pc-windows-gnu"]),
+        target("x86_64-pc-windows-gnu", &[]),
+    ];
+    assert_eq!(
+        super::alias_collisions(&targets),
+        ["target i686-pc-windows-gnu: alias `x86_64-pc-windows-gnu` is the name of a target"]
+    );
+}
+
+#[test]
+fn broken_target_links() {
+    let target = |name: &str, overview: &str| TargetInfo {
+        name: name.to_owned(),
+        sections: vec![("Overview".to_owned(), overview.to_owned())],
+        ..Default::default()
+    };
+    let targets = [
+        target(
+            "x86_64-unknown-linux-gnu",
+            "See [musl](./x86_64-unknown-linux-musl.md) and [the book](../../index.md).",
+        ),
+        target(
+            "x86_64-unknown-linux-musl",
+            "Like [glibc](targets/x86_64-unknown-linux-gnu.md#testing), not [gnux](./x86_64-unknown-linux-gnux.md).",
+        ),
+    ];
+    assert_eq!(
+        super::broken_target_links(&targets),
+        ["target x86_64-unknown-linux-musl, section Overview: links to unknown target \
+             `x86_64-unknown-linux-gnux`"]
+    );
+}
@@ -0,0 +1,263 @@
+//! The command line interface, turned into the [`Args`] of a run.
+
+use clap::Parser;
+use eyre::{bail, Context, OptionExt, Result};
+use std::path::PathBuf;
+
+use crate::{parse::TriStateBool, render::RenderOptions, Args, CheckFormat, PageFilter, SortOrder};
+
+/// Generates the target pages of the rustc book from the target info files.
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    /// Path to the target_infos directory containing the target source md files
+    /// (src/doc/rustc/target_infos/).
+    #[arg(
+        value_name = "TARGET_INFO_DIR",
+        required_unless_present_any = ["self_test", "emit_schema", "input_dir_flag"]
+    )]
+    input_dir: Option<String>,
+    /// Path to the `src` output directory (build/$target/md-doc/rustc/src).
+    #[arg(
+        value_name = "OUTPUT_SRC_DIR",
+        required_unless_present_any = ["self_test", "emit_schema", "output_src_flag"]
+    )]
+    output_src: Option<String>,
+    /// The target_infos directory, instead of the first positional argument.
+    #[arg(long = "input-dir", value_name = "TARGET_INFO_DIR", conflicts_with = "input_dir")]
+    input_dir_flag: Option<String>,
+    /// The `src` output directory, instead of the second positional argument.
+    #[arg(long = "output-src", value_name = "OUTPUT_SRC_DIR", conflicts_with = "output_src")]
+    output_src_flag: Option<String>,
+    /// Check that the output is up to date instead of writing it, like TARGET_CHECK_ONLY=1.
+    #[arg(long)]
+    check: bool,
+    /// The rustc to document, instead of the `RUSTC` env var or the `rustc` on the PATH.
+    #[arg(long, value_name = "PATH")]
+    rustc: Option<PathBuf>,
+    /// Write a Graphviz DOT file of the pattern-to-target mapping.
+    #[arg(long, value_name = "PATH")]
+    graph: Option<PathBuf>,
+    /// Print which patterns contribute what to the target, instead of generating docs.
+    #[arg(long, value_name = "TARGET")]
+    explain: Option<String>,
+    /// Print the cfg values of the target, instead of generating docs.
+    #[arg(long, value_name = "TARGET")]
+    dump_cfg: Option<String>,
+    /// Write a table of the patterns matching every target.
+    #[arg(long, value_name = "PATH")]
+    dump_resolution: Option<PathBuf>,
+    /// Write a CSV file of all targets and their maintainers.
+    #[arg(long, value_name = "PATH")]
+    maintainers_csv: Option<PathBuf>,
+    /// Write the number of targets without any documented sections.
+    #[arg(long, value_name = "PATH")]
+    undocumented_count: Option<PathBuf>,
+    /// Write a newline-delimited list of the output files that changed.
+    #[arg(long, value_name = "PATH")]
+    write_manifest: Option<PathBuf>,
+    /// Use pre-captured rustc information from a JSON file instead of running rustc.
+    #[arg(long, value_name = "PATH")]
+    rustc_info: Option<PathBuf>,
+    /// Only process the targets in the newline-delimited list.
+    #[arg(long, value_name = "PATH")]
+    targets_from: Option<PathBuf>,
+    /// Only write the page of the target, without the pages listing all targets.
+    #[arg(long, value_name = "TARGET")]
+    only: Option<String>,
+    /// Add the owners of the target info files in a GitHub CODEOWNERS file to the maintainers.
+    #[arg(long, value_name = "PATH")]
+    codeowners: Option<PathBuf>,
+    /// Fail on the warnings about the target info files instead of printing them.
+    #[arg(long)]
+    strict: bool,
+    /// Validate that all maintainers are handles, emails or quoted free-form names.
+    #[arg(long)]
+    check_maintainers_format: bool,
+    /// Validate every target against the target tier policy.
+    #[arg(long)]
+    check_policy: bool,
+    /// Validate that the std and host metadata of all tier 1 and 2 targets is known.
+    #[arg(long)]
+    check_known_metadata: bool,
+    /// Only generate the pages of the targets with this std support.
+    #[arg(long, value_name = "VALUE")]
+    filter_std: Option<TriStateBool>,
+    /// Only generate the pages of the targets with this host tools support.
+    #[arg(long, value_name = "VALUE")]
+    filter_host: Option<TriStateBool>,
+    /// Pipe every target page through the shell command before writing it, the target is `$1`.
+    #[arg(long, value_name = "COMMAND", allow_hyphen_values = true)]
+    post_process_cmd: Option<String>,
+    /// Commit the changed output files to the git repository containing the output.
+    #[arg(long)]
+    commit: bool,
+    /// Remove the pages of targets that don't exist anymore.
+    #[arg(long)]
+    prune: bool,
+    /// Regenerate the docs whenever the target info files change.
+    #[arg(long)]
+    watch: bool,
+    /// Don't print progress messages.
+    #[arg(long)]
+    quiet: bool,
+    /// Run the whole pipeline on the fixtures and compare the output.
+    /// The fixtures are in `self-test` in the source checkout, relative to the working directory.
+    #[arg(long, value_name = "FIXTURES_DIR", num_args = 0..=1, default_missing_value = "self-test")]
+    self_test: Option<PathBuf>,
+    /// Write a JSON Schema of the frontmatter of the target info files, for editors.
+    #[arg(long, value_name = "PATH")]
+    emit_schema: Option<PathBuf>,
+    /// How the results of the run are reported.
+    #[arg(long, value_name = "FORMAT", default_value = "human")]
+    check_format: CheckFormat,
+    /// Order of the targets in the target lists and tables.
+    #[arg(long, value_name = "ORDER", default_value = "name")]
+    sort: SortOrder,
+    /// Render the target pages from a template instead of the built-in layout.
+    #[arg(long, value_name = "PATH")]
+    page_template: Option<String>,
+    /// Append a checksum stamp to every generated target page.
+    /// The other generated files, like SUMMARY.md and targets.md, are not stamped.
+    #[arg(long)]
+    stamp: bool,
+    /// Verify the checksum stamps of the existing target pages, instead of generating docs.
+    #[arg(long)]
+    verify_stamps: bool,
+    /// Prepend a comment with the tool and rustc versions and the time to every target page.
+    #[arg(long)]
+    stamp_provenance: bool,
+    /// Generate a landing page for every target_arch, listed in SUMMARY.md after the targets.
+    #[arg(long)]
+    arch_pages: bool,
+    /// Also write the information about all targets to targets.json.
+    #[arg(long)]
+    json: bool,
+    /// Replace the message on the pages of targets without maintainers.
+    #[arg(long, value_name = "MESSAGE", allow_hyphen_values = true)]
+    no_maintainers_message: Option<String>,
+    /// Render the message for targets without maintainers as a warning alert.
+    #[arg(long)]
+    no_maintainers_alert: bool,
+    /// Show the GitHub avatars of the maintainers, as inline HTML.
+    #[arg(long)]
+    maintainer_avatars: bool,
+    /// Render draft sections instead of stubbing them out.
+    #[arg(long)]
+    include_drafts: bool,
+    /// File extension of the target pages, md by default.
+    #[arg(long, value_name = "EXTENSION")]
+    page_extension: Option<String>,
+    /// Render the requirements of the targets as a checklist.
+    #[arg(long)]
+    checklist_requirements: bool,
+    /// Where the tiers on the target pages link to.
+    #[arg(long, value_name = "URL")]
+    tier_policy_url: Option<String>,
+    /// Truncate the notes in the platform support tables to this many characters.
+    #[arg(long, value_name = "LENGTH")]
+    max_notes_len: Option<usize>,
+    /// URL of the rendered book, for canonical URLs and absolute links between the pages.
+    #[arg(long, value_name = "URL")]
+    base_url: Option<String>,
+    /// Split the tier tables with more rows than this into one table per target_arch.
+    #[arg(long, value_name = "ROWS")]
+    split_tables_over: Option<usize>,
+    /// Render the cfg values as one list instead of grouping them by what they are about.
+    #[arg(long)]
+    flat_cfg: bool,
+    /// Collapse cfg lists with more entries than this.
+    #[arg(long, value_name = "COUNT")]
+    cfg_collapse_threshold: Option<usize>,
+}
+
+impl Cli {
+    /// Validates the arguments and parses their values.
+    pub fn into_args(self) -> Result<Args> {
+        if self.self_test.is_some() {
+            // The self-test brings its own input and output.
+            return Ok(Args { self_test: self.self_test, ..Default::default() });
+        }
+        if self.emit_schema.is_some() {
+            // The schema doesn't depend on any input.
+            return Ok(Args { emit_schema: self.emit_schema, ..Default::default() });
+        }
+
+        let input_dir = self.input_dir.or(self.input_dir_flag);
+        let input_dir = input_dir.ok_or_eyre("missing the target_infos directory")?;
+        let output_src = self.output_src.or(self.output_src_flag);
+        let output_src = output_src.ok_or_eyre("missing the `src` output directory")?;
+
+        if self.prune && self.targets_from.is_some() {
+            bail!(
+                "`--prune` can't be used with `--targets-from`, it would remove the other targets"
+            );
+        }
+        if self.prune && self.only.is_some() {
+            bail!("`--prune` can't be used with `--only`, it would remove the other targets");
+        }
+
+        let render_options = RenderOptions {
+            max_notes_len: self.max_notes_len,
+            arch_pages: self.arch_pages,
+            no_maintainers_message: self.no_maintainers_message,
+            no_maintainers_alert: self.no_maintainers_alert,
+            partial: self.targets_from.is_some(),
+            include_drafts: self.include_drafts,
+            checklist_requirements: self.checklist_requirements,
+            page_extension: self.page_extension,
+            tier_policy_url: self.tier_policy_url,
+            split_tables_over: self.split_tables_over,
+            cfg_collapse_threshold: self.cfg_collapse_threshold,
+            base_url: self.base_url,
+            maintainer_avatars: self.maintainer_avatars,
+            json: self.json,
+            flat_cfg: self.flat_cfg,
+        };
+
+        let page_template = self
+            .page_template
+            .map(|path| {
+                std::fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("reading page template {path}"))
+            })
+            .transpose()?;
+
+        Ok(Args {
+            input_dir,
+            output_src,
+            check: self.check,
+            rustc: self.rustc,
+            graph: self.graph,
+            explain: self.explain,
+            dump_cfg: self.dump_cfg,
+            dump_resolution: self.dump_resolution,
+            maintainers_csv: self.maintainers_csv,
+            undocumented_count: self.undocumented_count,
+            write_manifest: self.write_manifest,
+            rustc_info: self.rustc_info,
+            targets_from: self.targets_from,
+            only: self.only,
+            codeowners: self.codeowners,
+            strict: self.strict,
+            check_maintainers_format: self.check_maintainers_format,
+            check_format: self.check_format,
+            check_policy: self.check_policy,
+            check_known_metadata: self.check_known_metadata,
+            page_filter: PageFilter { std: self.filter_std, host: self.filter_host },
+            sort: self.sort,
+            page_template,
+            stamp: self.stamp,
+            verify_stamps: self.verify_stamps,
+            stamp_provenance: self.stamp_provenance,
+            prune: self.prune,
+            post_process_cmd: self.post_process_cmd,
+            commit: self.commit,
+            watch: self.watch,
+            quiet: self.quiet,
+            self_test: None,
+            emit_schema: None,
+            render_options,
+        })
+    }
+}
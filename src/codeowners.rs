@@ -0,0 +1,103 @@
+//! Reading maintainers from a GitHub `CODEOWNERS` file.
+
+use eyre::{Context, OptionExt, Result};
+use std::path::Path;
+
+use crate::parse::ParsedTargetInfoFile;
+
+/// The rules of a `CODEOWNERS` file, in the order of the file.
+pub struct CodeOwners {
+    /// The pattern as a glob for `glob_match` and the owners of the matching paths.
+    rules: Vec<(String, Vec<String>)>,
+}
+
+impl CodeOwners {
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let owners = parts.take_while(|owner| !owner.starts_with('#'));
+                Some((to_glob(pattern), owners.map(ToOwned::to_owned).collect()))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// The owners of a path relative to the repository root.
+    /// Like GitHub, the last matching rule wins, even if it has no owners.
+    pub fn owners(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob_match::glob_match(glob, path))
+            .map_or(&[], |(_, owners)| owners)
+    }
+}
+
+/// Converts a gitignore-style `CODEOWNERS` pattern into a glob matching the paths it covers.
+fn to_glob(pattern: &str) -> String {
+    // Patterns without a slash in the middle match at any depth, others relative to the root.
+    let glob = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_owned(),
+        None if !pattern.trim_end_matches('/').contains('/') => format!("**/{pattern}"),
+        None => pattern.to_owned(),
+    };
+    // A directory pattern covers everything inside it.
+    match glob.strip_suffix('/') {
+        Some(dir) => format!("{dir}/**"),
+        None if !glob.contains('*') => format!("{{{glob},{glob}/**}}"),
+        None => glob,
+    }
+}
+
+/// Loads the `CODEOWNERS` file and adds the owners of every target info file to its maintainers.
+pub fn merge_codeowners_file(
+    infos: &mut [ParsedTargetInfoFile],
+    codeowners: &Path,
+    input_dir: &Path,
+) -> Result<()> {
+    let content = std::fs::read_to_string(codeowners).wrap_err("reading file")?;
+
+    // The paths are relative to the repository root, which the file can be in,
+    // or in its `.github` or `docs` directory.
+    let file = codeowners.canonicalize()?;
+    let mut root = file.parent().ok_or_eyre("CODEOWNERS file without a directory")?;
+    if root.ends_with(".github") || root.ends_with("docs") {
+        root = root.parent().unwrap_or(root);
+    }
+    let input_dir = input_dir.canonicalize().wrap_err("finding the input directory")?;
+    let relative_input_dir = input_dir.strip_prefix(root).wrap_err_with(|| {
+        format!("the input directory is outside the repository root {}", root.display())
+    })?;
+    let relative_input_dir = relative_input_dir.iter().map(|part| part.to_string_lossy());
+
+    merge_codeowners(infos, &CodeOwners::parse(&content), &relative_input_dir.collect::<Vec<_>>());
+    Ok(())
+}
+
+/// Adds the owners of every target info file, which is in `input_dir` with its components
+/// relative to the repository root, to its maintainers.
+fn merge_codeowners(
+    infos: &mut [ParsedTargetInfoFile],
+    codeowners: &CodeOwners,
+    input_dir: &[impl AsRef<str>],
+) {
+    for info in infos {
+        let mut path =
+            input_dir.iter().map(|part| format!("{}/", part.as_ref())).collect::<String>();
+        path.push_str(&format!("{}.md", info.pattern));
+
+        for owner in codeowners.owners(&path) {
+            if !info.maintainers.contains(owner) {
+                info.maintainers.push(owner.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,41 @@
+use super::CodeOwners;
+use crate::parse::ParsedTargetInfoFile;
+
+#[test]
+fn owners() {
+    let codeowners = CodeOwners::parse(
+        "# Target docs\n\
+        *.md @docs\n\
+        /src/doc/rustc/ @rust-lang/docs # the book\n\
+        src/doc/rustc/target_infos/*-linux-gnu.md @penguin @rust-lang/linux\n\
+        /src/doc/rustc/target_infos/unowned.md\n",
+    );
+
+    let owners = |path| codeowners.owners(path).to_vec();
+    assert_eq!(owners("README.md"), ["@docs"]);
+    assert_eq!(owners("src/doc/rustc/book.toml"), ["@rust-lang/docs"]);
+    assert_eq!(
+        owners("src/doc/rustc/target_infos/*-linux-gnu.md"),
+        ["@penguin", "@rust-lang/linux"]
+    );
+    assert!(owners("src/doc/rustc/target_infos/unowned.md").is_empty());
+    assert!(owners("src/lib.rs").is_empty());
+}
+
+#[test]
+fn merge_codeowners() {
+    let info = |pattern: &str| ParsedTargetInfoFile {
+        pattern: pattern.to_owned(),
+        maintainers: vec!["@penguin".to_owned()],
+        ..Default::default()
+    };
+    let mut infos = [info("*-linux-gnu"), info("*-apple-darwin")];
+    let codeowners = CodeOwners::parse(
+        "/src/doc/rustc/target_infos/*-linux-gnu.md @penguin @tux\n\
+        /src/doc/rustc/target_infos/*-apple-darwin.md @apple\n",
+    );
+
+    super::merge_codeowners(&mut infos, &codeowners, &["src", "doc", "rustc", "target_infos"]);
+    assert_eq!(infos[0].maintainers, ["@penguin", "@tux"]);
+    assert_eq!(infos[1].maintainers, ["@penguin", "@apple"]);
+}
@@ -0,0 +1,54 @@
+//! Committing the generated files with git.
+
+use eyre::{bail, Context, OptionExt, Result};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
+/// Commits the files to the git repository that `dir` is in.
+/// Returns whether a commit was created, which it isn't if the files didn't change.
+pub fn commit_files(dir: &Path, files: &[PathBuf], message: &str) -> Result<bool> {
+    if files.is_empty() {
+        return Ok(false);
+    }
+    // Paths relative to the current directory are not relative to `dir`.
+    // Removed files don't exist anymore, so only their directory can be canonicalized.
+    let files = files
+        .iter()
+        .map(|file| {
+            let parent = file.parent().filter(|parent| !parent.as_os_str().is_empty());
+            let parent = parent.unwrap_or(Path::new(".")).canonicalize();
+            let name = file.file_name().ok_or_eyre("output file without a name")?;
+            Ok(parent.wrap_err_with(|| format!("finding {}", file.display()))?.join(name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let files = files.iter().map(|file| file.as_os_str());
+
+    let status = git(dir, ["add", "--"].map(OsStr::new).into_iter().chain(files.clone()))?;
+    if !status.success() {
+        bail!("`git add` failed with {status}");
+    }
+
+    // Exits with 1 if there are staged changes.
+    let diff = ["diff", "--cached", "--quiet", "--"].map(OsStr::new);
+    let status = git(dir, diff.into_iter().chain(files.clone()))?;
+    if status.success() {
+        return Ok(false);
+    }
+
+    let commit = ["commit", "--quiet", "-m", message, "--"].map(OsStr::new);
+    let status = git(dir, commit.into_iter().chain(files))?;
+    if !status.success() {
+        bail!("`git commit` failed with {status}");
+    }
+    Ok(true)
+}
+
+fn git<'a>(dir: &Path, args: impl IntoIterator<Item = &'a OsStr>) -> Result<ExitStatus> {
+    Command::new("git").current_dir(dir).args(args).status().wrap_err("running git")
+}
+
+#[cfg(test)]
+mod tests;
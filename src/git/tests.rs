@@ -0,0 +1,38 @@
+use std::{path::Path, process::Command};
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git").current_dir(dir).args(args).output().unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn commit_only_changes() {
+    let dir = std::env::temp_dir().join(format!("target-docs-git-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "--quiet"]);
+    git(&dir, &["config", "user.name", "Target Docs"]);
+    git(&dir, &["config", "user.email", "target-docs@example.com"]);
+    let commit_count = || git(&dir, &["rev-list", "--count", "HEAD"]).trim().to_owned();
+
+    let files = [dir.join("x86_64-unknown-linux-gnu.md")];
+    std::fs::write(&files[0], "# x86_64-unknown-linux-gnu\n").unwrap();
+    assert!(super::commit_files(&dir, &files, "Add docs").unwrap());
+    assert_eq!(commit_count(), "1");
+
+    // Reported as changed, but it has the committed content.
+    assert!(!super::commit_files(&dir, &files, "Update docs").unwrap());
+    assert!(!super::commit_files(&dir, &[], "Update docs").unwrap());
+    assert_eq!(commit_count(), "1");
+
+    std::fs::write(&files[0], "# x86_64-unknown-linux-gnu\n\n**Tier: 1**\n").unwrap();
+    assert!(super::commit_files(&dir, &files, "Update docs").unwrap());
+    assert_eq!(commit_count(), "2");
+    assert_eq!(git(&dir, &["log", "-1", "--format=%s"]), "Update docs\n");
+
+    std::fs::remove_file(&files[0]).unwrap();
+    assert!(super::commit_files(&dir, &files, "Remove docs").unwrap());
+    assert_eq!(commit_count(), "3");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,1200 @@
+//! Generates the target pages of the rustc book from the target info files.
+//!
+//! The `target-docs` binary is [`run_cli`]. Other tools can parse the target info files with
+//! [`parse::load_target_infos`] and resolve the information of a target with [`resolve_target`],
+//! or run the generation with [`generate`] and their own [`PostProcess`] hook.
+
+mod check;
+mod cli;
+mod codeowners;
+mod git;
+mod output;
+pub mod parse;
+mod render;
+mod rustc;
+mod self_test;
+mod stamp;
+mod watch;
+
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use clap::Parser;
+use eyre::{bail, Context, Result};
+use output::OutputWriter;
+use parse::{
+    CiLink, Footnote, MaintenanceStatus, ParsedTargetInfoFile, Proposal, SharedNotes, TestedBy,
+    ToolchainRequirement, TriStateBool,
+};
+use rustc::RustcTargetMetadata;
+use serde::Serialize;
+
+/// Information about a target obtained from the markdown and rustc.
+#[derive(Default)]
+struct TargetInfo {
+    name: String,
+    maintainers: Vec<String>,
+    sections: Vec<(String, String)>,
+    section_maintainers: Vec<(String, Vec<String>)>,
+    draft_sections: Vec<String>,
+    footnotes: Vec<Footnote>,
+    experimental: bool,
+    stabilized_in: Option<String>,
+    /// The tier from the target info files, the rendered one is rustc's `metadata.tier`.
+    tier: Option<u8>,
+    nightly_tier: Option<u8>,
+    min_versions: Vec<(String, String)>,
+    abi_notes: Option<String>,
+    binary_size_notes: Option<String>,
+    build_example: Option<String>,
+    ci: Option<CiLink>,
+    toolchain_requirements: Vec<ToolchainRequirement>,
+    proposals: Vec<Proposal>,
+    see_also: Vec<String>,
+    aliases: Vec<String>,
+    tested_by: Vec<TestedBy>,
+    maintenance_status: Option<MaintenanceStatus>,
+    /// The notes shared by all targets of one of the `target_family` of this target.
+    shared_notes: Vec<SharedNotes>,
+    /// The metadata fields that differ from most other targets matched by the same pattern.
+    family_outliers: Vec<&'static str>,
+    target_cfgs: Vec<(String, String)>,
+    metadata: RustcTargetMetadata,
+    /// Whether rustc failed for this target, leaving `target_cfgs` and `metadata` empty.
+    rustc_unavailable: bool,
+}
+
+/// Whether a section is on every doc page or only on the pages of targets that have it.
+#[derive(Clone, Copy, PartialEq)]
+enum SectionKind {
+    /// Stubbed out when not found.
+    Required,
+    /// Doesn't exist when not found.
+    Optional,
+}
+
+/// All the sections that the doc pages can have, in the order they are rendered.
+// IMPORTANT: This is also documented in the README, keep it in sync.
+const SECTIONS: &[(&str, SectionKind)] = &[
+    ("Overview", SectionKind::Required),
+    ("Requirements", SectionKind::Required),
+    ("Testing", SectionKind::Required),
+    ("Building the target", SectionKind::Required),
+    ("Cross compilation", SectionKind::Optional),
+    ("Building Rust programs", SectionKind::Optional),
+];
+
+/// The kind of the section with this name, if it is one.
+fn section_kind(name: &str) -> Option<SectionKind> {
+    SECTIONS.iter().find(|(section, _)| *section == name).map(|(_, kind)| *kind)
+}
+
+/// The names of all sections, for error messages.
+fn section_names() -> Vec<&'static str> {
+    SECTIONS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Command line arguments.
+#[derive(Default)]
+pub struct Args {
+    input_dir: String,
+    output_src: String,
+    /// Check that the output is up to date instead of writing it.
+    check: bool,
+    /// The rustc to document, instead of the one from `RUSTC` or the `PATH`.
+    rustc: Option<PathBuf>,
+    /// Path to write a Graphviz DOT file of the pattern-to-target mapping to.
+    graph: Option<PathBuf>,
+    /// Print which patterns contribute what to this target, instead of generating docs.
+    explain: Option<String>,
+    /// Print the cfg values of this target, instead of generating docs.
+    dump_cfg: Option<String>,
+    /// Path to write a table of the patterns matching every target to.
+    dump_resolution: Option<PathBuf>,
+    /// Path to write a CSV file of all targets and their maintainers to.
+    maintainers_csv: Option<PathBuf>,
+    /// Path to write the number of targets without any documented sections to.
+    undocumented_count: Option<PathBuf>,
+    /// Path to write a newline-delimited list of the output files that changed to.
+    write_manifest: Option<PathBuf>,
+    /// Path to a JSON file with pre-captured rustc information to use instead of running rustc.
+    rustc_info: Option<PathBuf>,
+    /// Path to a newline-delimited list of targets to process instead of all of rustc's targets.
+    targets_from: Option<PathBuf>,
+    /// Only write the page of this target, without the pages listing all targets.
+    only: Option<String>,
+    /// GitHub `CODEOWNERS` file whose owners of the target info files are added to the maintainers.
+    codeowners: Option<PathBuf>,
+    /// Fail on the warnings about the target info files instead of printing them.
+    strict: bool,
+    /// Validate that all maintainers are handles, emails or quoted free-form names.
+    check_maintainers_format: bool,
+    /// Validate every target against the target tier policy.
+    check_policy: bool,
+    /// Validate that the `std` and `host` metadata of all tier 1 and 2 targets is known.
+    check_known_metadata: bool,
+    /// Only generate the pages of the targets matching this filter.
+    page_filter: PageFilter,
+    /// Order of the targets in the target lists and tables.
+    sort: SortOrder,
+    /// Template for the target pages, replacing the built-in layout.
+    page_template: Option<String>,
+    /// Append a checksum stamp to every generated target page.
+    stamp: bool,
+    /// Verify the checksum stamps of the existing target pages, instead of generating docs.
+    verify_stamps: bool,
+    /// Prepend a comment with the tool and rustc versions and the time to every target page.
+    stamp_provenance: bool,
+    /// Remove the pages of targets that don't exist anymore.
+    prune: bool,
+    /// Command that every target page is piped through before it's written.
+    post_process_cmd: Option<String>,
+    /// Commit the changed output files to the git repository containing the output.
+    commit: bool,
+    /// How the results of the run are reported.
+    check_format: CheckFormat,
+    /// Regenerate the docs whenever the target info files change.
+    watch: bool,
+    /// Don't print progress messages.
+    quiet: bool,
+    /// Run the whole pipeline on the fixtures in this directory and compare the output.
+    self_test: Option<PathBuf>,
+    /// Write the JSON Schema of the frontmatter to this file instead of generating docs.
+    emit_schema: Option<PathBuf>,
+    render_options: render::RenderOptions,
+}
+
+/// Parses the arguments like the command line, starting with the name of the binary.
+pub fn parse_args(args: impl Iterator<Item = String>) -> Result<Args> {
+    cli::Cli::try_parse_from(args)?.into_args()
+}
+
+/// Restricts the generated target pages by the std and host tools support of the targets.
+/// The tables always contain all targets.
+#[derive(Default)]
+struct PageFilter {
+    std: Option<TriStateBool>,
+    host: Option<TriStateBool>,
+}
+
+impl PageFilter {
+    fn matches(&self, target: &TargetInfo) -> bool {
+        let matches = |filter: Option<TriStateBool>, value: Option<bool>| {
+            filter.is_none_or(|filter| filter == TriStateBool::from(value))
+        };
+        matches(self.std, target.metadata.std) && matches(self.host, target.metadata.host_tools)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum SortOrder {
+    /// Alphabetically by target name, so changes to rustc's order don't change the output.
+    #[default]
+    Name,
+    /// By tier, and alphabetically by target name within a tier.
+    TierThenName,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum CheckFormat {
+    /// Progress messages and errors for humans on stderr.
+    #[default]
+    Human,
+    /// The results as JSON on stdout, in addition to the human output.
+    Json,
+}
+
+/// The machine-readable results of a run, for CI annotations and bots.
+#[derive(Serialize)]
+struct CheckSummary {
+    /// The output files that changed, or would change in check mode.
+    changed_files: Vec<PathBuf>,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+fn check_summary_json(result: &Result<Vec<PathBuf>>, warnings: &[String]) -> String {
+    let summary = CheckSummary {
+        changed_files: result.as_ref().ok().cloned().unwrap_or_default(),
+        errors: result.as_ref().err().map(|err| format!("{err:#}")).into_iter().collect(),
+        warnings: warnings.to_vec(),
+    };
+    serde_json::to_string_pretty(&summary).expect("serializing the check summary")
+}
+
+fn sort_targets(targets: &mut [TargetInfo], order: SortOrder) {
+    match order {
+        SortOrder::Name => targets.sort_by(|a, b| a.name.cmp(&b.name)),
+        SortOrder::TierThenName => targets.sort_by(|a, b| {
+            // Targets without a tier go last.
+            let tier = |target: &TargetInfo| target.metadata.tier.unwrap_or(u8::MAX);
+            tier(a).cmp(&tier(b)).then_with(|| a.name.cmp(&b.name))
+        }),
+    }
+}
+
+/// Prints progress messages, unless it is quiet, and warnings.
+pub struct Progress<W> {
+    out: Option<W>,
+    warnings: Vec<String>,
+}
+
+impl<W: Write> Progress<W> {
+    pub fn new(quiet: bool, out: W) -> Self {
+        Self { out: (!quiet).then_some(out), warnings: Vec::new() }
+    }
+
+    /// Prints the warning to stderr even when quiet, and keeps it for the check summary.
+    fn warning(&mut self, warning: String) {
+        eprintln!("warning: {warning}");
+        self.warnings.push(warning);
+    }
+
+    fn message(&mut self, message: &str) {
+        if let Some(out) = &mut self.out {
+            // Failing to print progress is not worth aborting over.
+            let _ = writeln!(out, "{message}");
+        }
+    }
+}
+
+/// Runs the `target-docs` command line interface with the arguments of the process.
+pub fn run_cli() -> Result<()> {
+    // Unlike `parse_args`, this prints the help and usage errors like other CLIs.
+    let args = cli::Cli::parse().into_args()?;
+    if let Some(fixtures) = &args.self_test {
+        return self_test::run(fixtures);
+    }
+    if let Some(path) = &args.emit_schema {
+        let schema = serde_json::to_string_pretty(&parse::schema::frontmatter_schema())?;
+        return std::fs::write(path, schema + "\n").wrap_err("writing the frontmatter schema");
+    }
+
+    let quiet = args.quiet || std::env::var_os("NO_PROGRESS").is_some_and(|var| !var.is_empty());
+    let check_only = args.check || std::env::var("TARGET_CHECK_ONLY") == Ok("1".to_owned());
+    if args.watch {
+        // Every run has to start from the original static files, the first one fills them in.
+        let static_files = render::STATIC_FILES
+            .map(|file| Path::new(&args.output_src).join(file))
+            .into_iter()
+            .map(|path| {
+                let content = std::fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("reading {}", path.display()))?;
+                Ok((path, content))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return watch::watch(Path::new(&args.input_dir), || {
+            if !check_only {
+                for (path, content) in &static_files {
+                    std::fs::write(path, content)
+                        .wrap_err_with(|| format!("restoring {}", path.display()))?;
+                }
+            }
+            let changed =
+                generate(&args, check_only, &mut Progress::new(quiet, std::io::stderr()), None)?;
+            Ok(changed.len())
+        });
+    }
+
+    let mut progress = Progress::new(quiet, std::io::stderr());
+    let result = generate(&args, check_only, &mut progress, None);
+    if args.check_format == CheckFormat::Json {
+        println!("{}", check_summary_json(&result, &progress.warnings));
+    }
+    result.map(drop)
+}
+
+/// Generates the docs, or does one of the other things the arguments ask for instead.
+/// Every target page is passed through `post_process`, after `--post-process-cmd`.
+/// Returns the output files that changed.
+pub fn generate(
+    args: &Args,
+    check_only: bool,
+    progress: &mut Progress<impl Write>,
+    post_process: Option<PostProcess<'_>>,
+) -> Result<Vec<PathBuf>> {
+    let input_dir = &args.input_dir;
+    let output_src = &args.output_src;
+
+    progress.message(&format!("Loading target info docs from {input_dir}"));
+    progress.message(&format!("Writing output to {output_src}"));
+
+    if check_only && args.commit {
+        bail!(
+            "`--commit` can't be used with `--check` (or TARGET_CHECK_ONLY=1), nothing is written"
+        );
+    }
+
+    if args.verify_stamps {
+        let targets_dir = Path::new(output_src).join("platform-support").join("targets");
+        verify_stamps(&targets_dir, args.render_options.page_extension())?;
+        return Ok(Vec::new());
+    }
+
+    if let Some(target) = &args.dump_cfg {
+        print!("{}", dump_cfg(target, args.rustc_info.as_deref(), args.rustc.as_deref())?);
+        return Ok(Vec::new());
+    }
+
+    let mut infos = parse::load_target_infos(Path::new(input_dir), progress)
+        .wrap_err("failed loading target_info")?;
+    if let Some(path) = &args.codeowners {
+        codeowners::merge_codeowners_file(&mut infos, path, Path::new(input_dir))
+            .wrap_err_with(|| format!("loading maintainers from {}", path.display()))?;
+    }
+    let input_warnings = infos
+        .iter()
+        .flat_map(|info| {
+            let pasted = parse::pasted_frontmatter_warnings(info);
+            pasted.into_iter().chain(parse::frontmatter_whitespace_warnings(info))
+        })
+        .collect::<Vec<_>>();
+    if args.strict && !input_warnings.is_empty() {
+        bail!("the target info files have problems:\n{}", input_warnings.join("\n"));
+    }
+    for warning in input_warnings {
+        progress.warning(warning);
+    }
+
+    if let Some(target) = &args.explain {
+        print!("{}", explain_target(&infos, target));
+        return Ok(Vec::new());
+    }
+
+    let (targets, rustc_infos): (Vec<_>, Vec<_>) = match &args.rustc_info {
+        Some(path) => {
+            let infos = rustc::load_rustc_info_dump(path)
+                .wrap_err_with(|| format!("loading rustc info from {}", path.display()))?;
+            let all_targets = infos.iter().map(|(target, _)| target.clone()).collect();
+            let targets = selected_targets(all_targets, args)?;
+            let infos = infos.into_iter().filter(|(target, _)| targets.contains(target));
+            infos.map(|(target, info)| (target, Ok(info))).unzip()
+        }
+        None => {
+            let rustc = rustc_path(args.rustc.as_deref())?;
+            let targets = rustc::rustc_stdout(&rustc, &["--print", "target-list"])?;
+            let targets = selected_targets(targets.lines().map(ToOwned::to_owned).collect(), args)?;
+
+            progress.message("Collecting rustc information");
+            let rustc_infos = rustc_target_infos(&rustc, &targets)?;
+            (targets, rustc_infos)
+        }
+    };
+
+    // A target rustc fails for is still documented, without the information from rustc.
+    let mut rustc_failures = Vec::new();
+    let mut targets = targets
+        .iter()
+        .map(|target| resolve_target(&infos, target))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .zip(rustc_infos)
+        .map(|(md, rustc)| {
+            let rustc_unavailable = rustc.is_err();
+            let rustc = rustc.unwrap_or_else(|err| {
+                rustc_failures.push(format!("{}: {err:#}", md.name));
+                rustc::RustcTargetInfo::default()
+            });
+            TargetInfo {
+                name: md.name,
+                maintainers: md.maintainers,
+                sections: md.sections,
+                section_maintainers: md.section_maintainers,
+                draft_sections: md.draft_sections,
+                footnotes: md.footnotes,
+                experimental: md.experimental,
+                stabilized_in: md.stabilized_in,
+                tier: md.tier,
+                nightly_tier: md.nightly_tier,
+                min_versions: md.min_versions,
+                abi_notes: md.abi_notes,
+                binary_size_notes: md.binary_size_notes,
+                build_example: md.build_example,
+                ci: md.ci,
+                toolchain_requirements: md.toolchain_requirements,
+                proposals: md.proposals,
+                see_also: md.see_also,
+                aliases: md.aliases,
+                tested_by: md.tested_by,
+                maintenance_status: md.maintenance_status,
+                family_outliers: Vec::new(),
+                shared_notes: Vec::new(),
+                target_cfgs: rustc.target_cfgs,
+                metadata: rustc.metadata,
+                rustc_unavailable,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let shared_notes = parse::load_shared_notes(Path::new(input_dir))
+        .wrap_err("failed loading the shared notes")?;
+    apply_shared_notes(&mut targets, &shared_notes);
+
+    let family_outliers = check::family_outliers(&infos, &targets);
+    for (target, outliers) in targets.iter_mut().zip(family_outliers) {
+        target.family_outliers = outliers;
+    }
+
+    sort_targets(&mut targets, args.sort);
+
+    let platform_support =
+        std::fs::read_to_string(Path::new(output_src).join("platform-support.md"))
+            .wrap_err("reading platform-support.md")?;
+    let undefined_footnotes = check::undefined_footnotes(&infos, &platform_support);
+    if !undefined_footnotes.is_empty() {
+        bail!("invalid `footnotes`:\n{}", undefined_footnotes.join("\n"));
+    }
+
+    let alias_collisions = check::alias_collisions(&targets);
+    if !alias_collisions.is_empty() {
+        bail!("invalid `aliases`:\n{}", alias_collisions.join("\n"));
+    }
+
+    let self_references = check::see_also_self_references(&targets);
+    if !self_references.is_empty() {
+        bail!("invalid `see_also`:\n{}", self_references.join("\n"));
+    }
+    for cycle in check::see_also_cycles(&targets) {
+        progress.warning(format!(
+            "the targets reference each other in a `see_also` cycle: {}",
+            cycle.join(" -> ")
+        ));
+    }
+
+    if args.check_maintainers_format {
+        let violations = check::maintainer_format_violations(&targets);
+        if !violations.is_empty() {
+            bail!("invalid maintainers:\n{}", violations.join("\n"));
+        }
+    }
+
+    if args.check_policy {
+        let violations = check::policy_violations(&targets);
+        if !violations.is_empty() {
+            bail!("targets violate the target tier policy:\n{}", violations.join("\n"));
+        }
+    }
+
+    if args.check_known_metadata {
+        let violations = check::unknown_metadata_violations(&targets);
+        if !violations.is_empty() {
+            bail!("tier 1 and 2 targets have unknown metadata:\n{}", violations.join("\n"));
+        }
+    }
+
+    let tier_mismatches = check::tier_mismatches(&targets);
+    if !tier_mismatches.is_empty() {
+        let message = format!("mismatched tiers:\n{}", tier_mismatches.join("\n"));
+        if check_only {
+            bail!(message);
+        }
+        progress.warning(message);
+    }
+
+    progress.message(&format!("Rendering targets check_only={check_only}"));
+    let mut writer = OutputWriter::new(check_only);
+    let targets_dir = Path::new(output_src).join("platform-support").join("targets");
+    writer.create_dir_all(&targets_dir).wrap_err("creating platform-support/targets dir")?;
+    let post_process_cmd = args.post_process_cmd.as_ref().map(|cmd| -> PostProcess {
+        Box::new(move |target, content| run_post_process_cmd(cmd, target, content))
+    });
+    let post_process = match (post_process_cmd, post_process) {
+        (Some(cmd), Some(hook)) => {
+            Some(Box::new(move |target: &str, content| hook(target, cmd(target, content)?))
+                as PostProcess)
+        }
+        (cmd, hook) => cmd.or(hook),
+    };
+    let provenance = if args.stamp_provenance {
+        // The dump doesn't record the version of the rustc it was captured from.
+        let rustc_version = match &args.rustc_info {
+            Some(_) => "unknown (--rustc-info)".to_owned(),
+            None => rustc::rustc_stdout(&rustc_path(args.rustc.as_deref())?, &["--version"])?
+                .trim()
+                .to_owned(),
+        };
+        let source_date_epoch = std::env::var("SOURCE_DATE_EPOCH").ok();
+        Some(stamp::provenance_comment(&rustc_version, source_date_epoch.as_deref())?)
+    } else {
+        None
+    };
+    for info in targets.iter().filter(|target| args.page_filter.matches(target)) {
+        let doc = render_page(info, args, post_process.as_ref(), provenance.as_deref())?;
+        writer
+            .write(
+                &Path::new(output_src)
+                    .join(render::target_page_src_path(&info.name, &args.render_options)),
+                &doc,
+            )
+            .wrap_err("writing target file")?;
+    }
+
+    if args.prune {
+        let extension = args.render_options.page_extension();
+        prune_target_pages(&mut writer, &targets_dir, &targets, extension)?;
+    }
+
+    if let Some(graph) = &args.graph {
+        let dot = render::render_graph(&infos, &targets);
+        std::fs::write(graph, dot).wrap_err("writing graph file")?;
+    }
+
+    if let Some(path) = &args.dump_resolution {
+        let table = render::render_resolution(&infos, &targets);
+        std::fs::write(path, table).wrap_err("writing resolution dump")?;
+    }
+
+    if let Some(path) = &args.maintainers_csv {
+        std::fs::write(path, render::render_maintainers_csv(&targets))
+            .wrap_err("writing maintainers CSV")?;
+    }
+
+    if let Some(path) = &args.undocumented_count {
+        std::fs::write(path, format!("{}\n", count_undocumented(&targets)))
+            .wrap_err("writing undocumented count")?;
+    }
+
+    // With only some of the targets, patterns for the other targets are expectedly unused.
+    let all_targets = args.targets_from.is_none() && args.only.is_none();
+    let checked_patterns = if all_targets { infos.as_slice() } else { &[] };
+    for target_pattern in checked_patterns {
+        if !targets
+            .iter()
+            .any(|target| glob_match::glob_match(&target_pattern.pattern, &target.name))
+        {
+            bail!("target pattern `{}` was never used", target_pattern.pattern);
+        }
+
+        for footnote_target in target_pattern.footnotes.keys() {
+            if !targets.iter().any(|target| target.name == *footnote_target) {
+                bail!(
+                    "in target pattern `{}`, the footnotes for target `{}` were never used",
+                    target_pattern.pattern,
+                    footnote_target,
+                );
+            }
+        }
+    }
+
+    // The links to the pages of the other targets can't be checked with only some of them.
+    let broken_links = if all_targets { check::broken_target_links(&targets) } else { Vec::new() };
+    if !broken_links.is_empty() {
+        let message = format!("broken links to target pages:\n{}", broken_links.join("\n"));
+        if check_only {
+            bail!(message);
+        }
+        progress.warning(message);
+    }
+
+    // New rustc targets are often added without writing a target info file for them.
+    let unmatched = targets
+        .iter()
+        .filter(|target| {
+            !infos.iter().any(|pattern| glob_match::glob_match(&pattern.pattern, &target.name))
+        })
+        .map(|target| target.name.as_str())
+        .collect::<Vec<_>>();
+    if !unmatched.is_empty() {
+        let message =
+            format!("targets not matched by any target pattern:\n{}", unmatched.join("\n"));
+        if check_only {
+            bail!(message);
+        }
+        progress.warning(message);
+    }
+
+    // The pages listing all targets can't be rendered from a single one.
+    if args.only.is_none() {
+        render::render_static(&mut writer, Path::new(output_src), &targets, &args.render_options)?;
+    }
+
+    // In check mode, the links point to the files as they were before.
+    if !check_only {
+        // The pages left out by the page filter are still linked to from the lists of all targets.
+        let filtered_out = targets
+            .iter()
+            .filter(|target| !args.page_filter.matches(target))
+            .map(|target| {
+                Path::new(output_src)
+                    .join(render::target_page_src_path(&target.name, &args.render_options))
+            })
+            .collect::<Vec<_>>();
+        let dangling = writer.dangling_links(&filtered_out)?;
+        if !dangling.is_empty() {
+            bail!("the output links to files that don't exist:\n{}", dangling.join("\n"));
+        }
+    }
+
+    if let Some(path) = &args.write_manifest {
+        writer.write_manifest(path)?;
+    }
+    progress.message(&format!("{} output files changed", writer.changed().len()));
+
+    if args.commit {
+        let message = commit_message(writer.changed());
+        if git::commit_files(Path::new(output_src), writer.changed(), &message)? {
+            progress.message("Committed the changed output files");
+        }
+    }
+
+    let unmaintained = check::unmaintained_targets(&targets);
+    if !unmaintained.is_empty() {
+        let groups = unmaintained.iter().map(|(tier, names)| match tier {
+            Some(tier) => format!("tier {tier}: {}", names.join(", ")),
+            None => format!("unknown tier: {}", names.join(", ")),
+        });
+        let message =
+            format!("targets without maintainers:\n{}", groups.collect::<Vec<_>>().join("\n"));
+        // The target tier policy requires maintainers, but tier 3 targets are only warned about.
+        if check_only && unmaintained.iter().any(|(tier, _)| matches!(tier, Some(1 | 2))) {
+            bail!(message);
+        }
+        progress.warning(message);
+    }
+
+    if !rustc_failures.is_empty() {
+        let failures = format!(
+            "rustc failed for some targets, their information from rustc is missing:\n{}",
+            rustc_failures.join("\n")
+        );
+        if check_only {
+            bail!(failures);
+        }
+        progress.warning(failures);
+    }
+
+    progress.message("Finished generating target docs");
+    Ok(writer.changed().to_vec())
+}
+
+/// Asks rustc about the targets, through the cache file in `TARGET_DOCS_CACHE` if it's set.
+fn rustc_target_infos(
+    rustc: &Path,
+    targets: &[String],
+) -> Result<Vec<Result<rustc::RustcTargetInfo>>> {
+    match std::env::var_os("TARGET_DOCS_CACHE").filter(|path| !path.is_empty()) {
+        Some(cache) => rustc::cached_rustc_target_infos(rustc, targets, Path::new(&cache))
+            .wrap_err("using the rustc cache"),
+        None => Ok(rustc::rustc_target_infos(rustc, targets)),
+    }
+}
+
+/// The rustc from `--rustc`, or else from the `RUSTC` env var or the `PATH`.
+fn rustc_path(rustc: Option<&Path>) -> Result<PathBuf> {
+    match rustc {
+        Some(rustc) => Ok(rustc.to_owned()),
+        None => find_rustc(std::env::var_os("RUSTC"), std::env::var_os("PATH")),
+    }
+}
+
+/// Uses the rustc from the `RUSTC` env var, falling back to a `rustc` on the `PATH`.
+fn find_rustc(rustc_var: Option<OsString>, path_var: Option<OsString>) -> Result<PathBuf> {
+    if let Some(rustc) = rustc_var.filter(|rustc| !rustc.is_empty()) {
+        return Ok(PathBuf::from(rustc));
+    }
+
+    let rustc = format!("rustc{}", std::env::consts::EXE_SUFFIX);
+    let mut on_path = path_var.iter().flat_map(std::env::split_paths).map(|dir| dir.join(&rustc));
+    match on_path.find(|rustc| rustc.is_file()) {
+        Some(rustc) => Ok(rustc),
+        None => bail!(
+            "`RUSTC` is not set and there is no `rustc` on the `PATH`, \
+            pass `--rustc` with the rustc to document or `--rustc-info`"
+        ),
+    }
+}
+
+/// Renders the sorted cfg values of a target, one `key=value` per line like `--print cfg`.
+fn dump_cfg(target: &str, rustc_info: Option<&Path>, rustc: Option<&Path>) -> Result<String> {
+    let mut target_cfgs = match rustc_info {
+        Some(path) => {
+            let infos = rustc::load_rustc_info_dump(path)
+                .wrap_err_with(|| format!("loading rustc info from {}", path.display()))?;
+            let info = infos.into_iter().find(|(name, _)| name == target);
+            info.ok_or_else(|| eyre::eyre!("{} doesn't contain {target}", path.display()))?.1
+        }
+        None => rustc_target_infos(&rustc_path(rustc)?, &[target.to_owned()])?.remove(0)?,
+    }
+    .target_cfgs;
+
+    target_cfgs.sort();
+    Ok(target_cfgs.iter().map(|(key, value)| format!("{key}={value}\n")).collect())
+}
+
+/// The targets to document out of all of rustc's targets, restricted by `--targets-from`
+/// and `--only`.
+fn selected_targets(all_targets: Vec<String>, args: &Args) -> Result<Vec<String>> {
+    let targets = match &args.targets_from {
+        Some(list) => restrict_targets(all_targets, list)?,
+        None => all_targets,
+    };
+    match &args.only {
+        Some(only) if targets.contains(only) => Ok(vec![only.clone()]),
+        Some(only) => {
+            // Names differing in more than a third of the characters are other targets, not typos.
+            let mut closest = targets
+                .iter()
+                .map(|target| (edit_distance(target, only), target))
+                .filter(|(distance, _)| *distance <= only.chars().count() / 3)
+                .collect::<Vec<_>>();
+            closest.sort();
+            let closest = closest.iter().take(3).map(|(_, target)| format!("`{target}`"));
+            let closest = closest.collect::<Vec<_>>();
+            if closest.is_empty() {
+                bail!("unknown target `{only}`");
+            }
+            bail!("unknown target `{only}`, did you mean {}?", closest.join(", "));
+        }
+        None => Ok(targets),
+    }
+}
+
+/// The Levenshtein distance between the strings, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Restricts the targets to the ones listed in the newline-delimited file, keeping rustc's order.
+fn restrict_targets(all_targets: Vec<String>, list: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(list)
+        .wrap_err_with(|| format!("reading target list {}", list.display()))?;
+    let listed = content.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>();
+
+    let unknown = listed.iter().filter(|target| !all_targets.iter().any(|t| t == *target));
+    let unknown = unknown.map(|target| format!("`{target}`")).collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        bail!("{} lists unknown targets: {}", list.display(), unknown.join(", "));
+    }
+
+    Ok(all_targets.into_iter().filter(|target| listed.contains(&target.as_str())).collect())
+}
+
+/// Removes the target pages in the directory that don't belong to any of the targets.
+fn prune_target_pages(
+    writer: &mut OutputWriter,
+    targets_dir: &Path,
+    targets: &[TargetInfo],
+    page_extension: &str,
+) -> Result<()> {
+    let mut orphaned = Vec::new();
+    for entry in std::fs::read_dir(targets_dir).wrap_err("reading targets dir")? {
+        let path = entry?.path();
+        let is_page = path.extension().is_some_and(|extension| extension == page_extension);
+        let target = path.file_stem().and_then(|stem| stem.to_str());
+        // Pages kept for the legacy names of a target are still current.
+        let is_current = |name: &str| {
+            targets.iter().any(|t| t.name == name || t.aliases.iter().any(|alias| alias == name))
+        };
+        if is_page && !target.is_some_and(is_current) {
+            orphaned.push(path);
+        }
+    }
+
+    orphaned.sort();
+    for path in orphaned {
+        writer.remove(&path).wrap_err_with(|| format!("removing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Transforms the content of a target page before it's written, given the target name.
+pub type PostProcess<'a> = Box<dyn Fn(&str, String) -> Result<String> + 'a>;
+
+/// Renders the page of a target, with the template, post-processing and stamps if requested.
+fn render_page(
+    info: &TargetInfo,
+    args: &Args,
+    post_process: Option<&PostProcess<'_>>,
+    provenance: Option<&str>,
+) -> Result<String> {
+    let mut doc = match &args.page_template {
+        Some(template) => {
+            render::render_target_md_with_template(info, template, &args.render_options)?
+        }
+        None => render::render_target_md(info, &args.render_options),
+    };
+    if let Some(post_process) = post_process {
+        doc = post_process(&info.name, doc)
+            .wrap_err_with(|| format!("post-processing the page of {}", info.name))?;
+    }
+    if let Some(provenance) = provenance {
+        doc.insert_str(0, provenance);
+    }
+    // The stamp comes last, it covers the final content.
+    if args.stamp {
+        doc = stamp::add_stamp(&doc);
+    }
+    Ok(doc)
+}
+
+/// Runs the command with the target name as its argument, piping the page through it.
+fn run_post_process_cmd(cmd: &str, target: &str, content: String) -> Result<String> {
+    // The target is `$1` of the shell command.
+    let mut child = Command::new("sh")
+        .args(["-c", cmd, "sh", target])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .wrap_err_with(|| format!("spawning `{cmd}`"))?;
+
+    // Write on another thread so a command writing before it read everything can't deadlock.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = std::thread::spawn(move || stdin.write_all(content.as_bytes()));
+    let output = child.wait_with_output().wrap_err_with(|| format!("running `{cmd}`"))?;
+    writer.join().expect("stdin writer panicked").wrap_err("writing page to stdin")?;
+
+    if !output.status.success() {
+        bail!("`{cmd}` failed with {}", output.status);
+    }
+    String::from_utf8(output.stdout).wrap_err_with(|| format!("`{cmd}` printed invalid UTF-8"))
+}
+
+/// The message for committing the changed output files, listing the targets with changed pages.
+fn commit_message(changed: &[PathBuf]) -> String {
+    let targets = changed
+        .iter()
+        .filter(|path| path.parent().is_some_and(|parent| parent.ends_with("targets")))
+        .filter_map(|path| path.file_stem()?.to_str())
+        .map(|target| format!("- {target}\n"))
+        .collect::<String>();
+
+    let mut message = "Update generated target docs\n".to_owned();
+    if !targets.is_empty() {
+        message.push_str(&format!("\nChanged targets:\n{targets}"));
+    }
+    message
+}
+
+/// Gives every target the shared notes of its `target_family` values.
+fn apply_shared_notes(targets: &mut [TargetInfo], shared_notes: &[SharedNotes]) {
+    for target in targets {
+        let families = target
+            .target_cfgs
+            .iter()
+            .filter(|(key, _)| key == "target_family")
+            .map(|(_, value)| value.trim_matches('"'))
+            .collect::<Vec<_>>();
+        target.shared_notes = shared_notes
+            .iter()
+            .filter(|notes| families.contains(&notes.family.as_str()))
+            .cloned()
+            .collect();
+    }
+}
+
+/// Counts the targets where all sections are stubbed out, as a documentation coverage metric.
+fn count_undocumented(targets: &[TargetInfo]) -> usize {
+    targets.iter().filter(|target| target.sections.is_empty()).count()
+}
+
+/// Verifies the checksum stamps of all target pages in the directory, reporting all edited pages.
+fn verify_stamps(targets_dir: &Path, extension: &str) -> Result<()> {
+    let mut failures = Vec::new();
+    for entry in std::fs::read_dir(targets_dir).wrap_err("reading targets dir")? {
+        let path = entry?.path();
+        // Other files next to the target pages were never stamped.
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != extension) {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("reading {}", path.display()))?;
+        if let Err(err) = stamp::verify_stamp(&content) {
+            failures.push(format!("{}: {err}", path.display()));
+        }
+    }
+
+    if !failures.is_empty() {
+        failures.sort();
+        bail!("target pages failed stamp verification:\n{}", failures.join("\n"));
+    }
+    Ok(())
+}
+
+/// Information about a target obtained from the target_info markdown file.
+#[derive(Debug)]
+pub struct TargetInfoMd {
+    pub name: String,
+    pub maintainers: Vec<String>,
+    pub sections: Vec<(String, String)>,
+    pub section_maintainers: Vec<(String, Vec<String>)>,
+    pub draft_sections: Vec<String>,
+    pub footnotes: Vec<Footnote>,
+    pub experimental: bool,
+    pub stabilized_in: Option<String>,
+    pub tier: Option<u8>,
+    pub nightly_tier: Option<u8>,
+    pub min_versions: Vec<(String, String)>,
+    pub abi_notes: Option<String>,
+    pub binary_size_notes: Option<String>,
+    pub build_example: Option<String>,
+    pub ci: Option<CiLink>,
+    pub toolchain_requirements: Vec<ToolchainRequirement>,
+    pub proposals: Vec<Proposal>,
+    pub see_also: Vec<String>,
+    pub aliases: Vec<String>,
+    pub tested_by: Vec<TestedBy>,
+    pub maintenance_status: Option<MaintenanceStatus>,
+}
+
+/// Resolves the information of a single target from all patterns matching it, without rustc.
+///
+/// The patterns are the target info files from [`parse::load_target_infos`].
+/// Fails when the target inherits the same field from more than one pattern.
+pub fn resolve_target<'a>(
+    patterns: &'a [ParsedTargetInfoFile],
+    target: &str,
+) -> Result<TargetInfoMd> {
+    let mut maintainers = Vec::new();
+    let mut sections = Vec::new();
+    let mut section_maintainers = Vec::new();
+    let mut draft_sections = Vec::new();
+
+    let mut footnotes = Vec::new();
+    let mut experimental = false;
+    let mut stabilized_in = None;
+    let mut tier = None;
+    let mut nightly_tier = None;
+    let mut min_versions = Vec::<(String, String)>::new();
+    let mut abi_notes = None;
+    let mut binary_size_notes = None;
+    let mut build_example = None;
+    let mut ci = None;
+    let mut toolchain_requirements = Vec::<ToolchainRequirement>::new();
+    let mut proposals = Vec::new();
+    let mut see_also = Vec::<String>::new();
+    let mut aliases = Vec::<String>::new();
+    let mut tested_by = Vec::new();
+    let mut maintenance_status = None;
+
+    // The pattern setting each of the values only one pattern may set, to name both on conflicts.
+    let mut set_by = Vec::<(String, &str)>::new();
+    let mut inherit = |what: String, pattern: &'a str| -> Result<()> {
+        if let Some((_, earlier)) = set_by.iter().find(|(other, _)| *other == what) {
+            bail!(
+                "target {target} inherits {what} from multiple patterns, `{earlier}` and `{pattern}`, \
+                create a more specific pattern and add it there"
+            );
+        }
+        set_by.push((what, pattern));
+        Ok(())
+    };
+
+    for target_pattern in patterns {
+        let pattern = target_pattern.pattern.as_str();
+        if glob_match::glob_match(pattern, target) {
+            // Broad and specific patterns often list the same maintainers.
+            for maintainer in &target_pattern.maintainers {
+                let maintainer = maintainer.trim();
+                if !maintainers.iter().any(|existing| existing == maintainer) {
+                    maintainers.push(maintainer.to_owned());
+                }
+            }
+            proposals.extend_from_slice(&target_pattern.proposals);
+            tested_by.extend_from_slice(&target_pattern.tested_by);
+            for related in &target_pattern.see_also {
+                if !see_also.contains(related) {
+                    see_also.push(related.clone());
+                }
+            }
+            for alias in &target_pattern.aliases {
+                if !aliases.contains(alias) {
+                    aliases.push(alias.clone());
+                }
+            }
+            experimental |= target_pattern.experimental;
+
+            if let Some(status) = target_pattern.maintenance_status {
+                inherit("`maintenance_status`".to_owned(), pattern)?;
+                maintenance_status = Some(status);
+            }
+
+            if let Some(version) = &target_pattern.stabilized_in {
+                inherit("`stabilized_in`".to_owned(), pattern)?;
+                stabilized_in = Some(version.clone());
+            }
+
+            if let Some(pattern_tier) = target_pattern.tier {
+                inherit("`tier`".to_owned(), pattern)?;
+                tier = Some(pattern_tier);
+            }
+
+            if let Some(tier) = target_pattern.nightly_tier {
+                inherit("`nightly_tier`".to_owned(), pattern)?;
+                nightly_tier = Some(tier);
+            }
+
+            for (name, version) in &target_pattern.min_versions {
+                inherit(format!("the minimum {name} version"), pattern)?;
+                min_versions.push((name.clone(), version.clone()));
+            }
+
+            if let Some(notes) = &target_pattern.abi_notes {
+                inherit("`abi_notes`".to_owned(), pattern)?;
+                abi_notes = Some(notes.clone());
+            }
+
+            if let Some(notes) = &target_pattern.binary_size_notes {
+                inherit("`binary_size_notes`".to_owned(), pattern)?;
+                binary_size_notes = Some(notes.clone());
+            }
+
+            if let Some(example) = &target_pattern.build_example {
+                inherit("`build_example`".to_owned(), pattern)?;
+                build_example = Some(example.clone());
+            }
+
+            if let Some(link) = &target_pattern.ci {
+                inherit("`ci`".to_owned(), pattern)?;
+                ci = Some(link.clone());
+            }
+
+            for requirement in &target_pattern.toolchain_requirements {
+                inherit(format!("the {} toolchain requirement", requirement.tool), pattern)?;
+                toolchain_requirements.push(requirement.clone());
+            }
+
+            for (section_name, content) in &target_pattern.sections {
+                inherit(format!("the section {section_name}"), pattern)?;
+                sections.push((section_name.clone(), content.clone()));
+
+                if let Some(entry) =
+                    target_pattern.section_maintainers.iter().find(|(name, _)| name == section_name)
+                {
+                    section_maintainers.push(entry.clone());
+                }
+                if target_pattern.draft_sections.contains(section_name) {
+                    draft_sections.push(section_name.clone());
+                }
+            }
+
+            if let Some(target_footnotes) = target_pattern.footnotes.get(target) {
+                inherit("footnotes".to_owned(), pattern)?;
+                footnotes = target_footnotes.clone();
+            }
+        }
+    }
+
+    Ok(TargetInfoMd {
+        name: target.to_owned(),
+        maintainers,
+        sections,
+        section_maintainers,
+        draft_sections,
+        footnotes,
+        experimental,
+        stabilized_in,
+        tier,
+        nightly_tier,
+        min_versions,
+        abi_notes,
+        binary_size_notes,
+        build_example,
+        ci,
+        toolchain_requirements,
+        proposals,
+        see_also,
+        aliases,
+        tested_by,
+        maintenance_status,
+    })
+}
+
+/// Explains which patterns match a target and what every one of them contributes,
+/// in the order they are resolved in by [`resolve_target`].
+fn explain_target(patterns: &[ParsedTargetInfoFile], target: &str) -> String {
+    let matching = patterns
+        .iter()
+        .filter(|pattern| glob_match::glob_match(&pattern.pattern, target))
+        .collect::<Vec<_>>();
+    if matching.is_empty() {
+        return format!("no pattern matches {target}\n");
+    }
+
+    let mut explanation = format!("patterns matching {target}, in resolution order:\n");
+    for pattern in matching {
+        let mut contributions = Vec::new();
+        if !pattern.maintainers.is_empty() {
+            contributions.push(format!("maintainers: {}", pattern.maintainers.join(", ")));
+        }
+        if !pattern.sections.is_empty() {
+            let names = pattern.sections.iter().map(|(name, _)| name.as_str());
+            contributions.push(format!("sections: {}", names.collect::<Vec<_>>().join(", ")));
+        }
+        if let Some(footnotes) = pattern.footnotes.get(target) {
+            let names = footnotes.iter().map(Footnote::name);
+            contributions.push(format!("footnotes: {}", names.collect::<Vec<_>>().join(", ")));
+        }
+        if pattern.experimental {
+            contributions.push("experimental".to_owned());
+        }
+        if let Some(status) = pattern.maintenance_status {
+            contributions.push(format!("maintenance status: {}", status.label()));
+        }
+        if let Some(version) = &pattern.stabilized_in {
+            contributions.push(format!("stabilized in: {version}"));
+        }
+        if let Some(tier) = pattern.tier {
+            contributions.push(format!("tier: {tier}"));
+        }
+        if let Some(tier) = pattern.nightly_tier {
+            contributions.push(format!("nightly tier: {tier}"));
+        }
+        for (name, version) in &pattern.min_versions {
+            contributions.push(format!("minimum {name} version: {version}"));
+        }
+        if pattern.abi_notes.is_some() {
+            contributions.push("ABI notes".to_owned());
+        }
+        if pattern.binary_size_notes.is_some() {
+            contributions.push("binary size notes".to_owned());
+        }
+        if pattern.build_example.is_some() {
+            contributions.push("build example".to_owned());
+        }
+        if let Some(ci) = &pattern.ci {
+            contributions.push(format!("CI: {}", ci.url));
+        }
+        for requirement in &pattern.toolchain_requirements {
+            let (tool, version) = (&requirement.tool, &requirement.min_version);
+            contributions.push(format!("{tool} toolchain requirement: {version}"));
+        }
+        if !pattern.see_also.is_empty() {
+            contributions.push(format!("see also: {}", pattern.see_also.join(", ")));
+        }
+        if !pattern.aliases.is_empty() {
+            contributions.push(format!("aliases: {}", pattern.aliases.join(", ")));
+        }
+        for tested_by in &pattern.tested_by {
+            contributions.push(format!("tested by: {}", tested_by.runner));
+        }
+        for proposal in &pattern.proposals {
+            contributions.push(format!("proposal: {}", proposal.url));
+        }
+        if contributions.is_empty() {
+            contributions.push("nothing".to_owned());
+        }
+
+        explanation.push_str(&format!("- `{}`\n", pattern.pattern));
+        for contribution in contributions {
+            explanation.push_str(&format!("  - {contribution}\n"));
+        }
+    }
+    explanation
+}
+
+#[cfg(test)]
+mod tests;
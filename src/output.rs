@@ -0,0 +1,125 @@
+//! Writing the generated files.
+
+use eyre::{Context, Result};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// Writes output files, keeping track of the files whose content changed.
+/// In check mode, nothing is written, but changes are still tracked.
+pub struct OutputWriter {
+    check_only: bool,
+    changed: Vec<PathBuf>,
+    /// All written files, including the ones that already had the content.
+    written: Vec<PathBuf>,
+}
+
+impl OutputWriter {
+    pub fn new(check_only: bool) -> Self {
+        Self { check_only, changed: Vec::new(), written: Vec::new() }
+    }
+
+    pub fn create_dir_all(&self, path: &Path) -> Result<()> {
+        if !self.check_only {
+            fs::create_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the file, unless it already has the content.
+    pub fn write(&mut self, path: &Path, content: &str) -> Result<()> {
+        self.written.push(path.to_owned());
+        if fs::read_to_string(path).is_ok_and(|old| old == content) {
+            return Ok(());
+        }
+
+        self.changed.push(path.to_owned());
+        if !self.check_only {
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the file, for output files that shouldn't exist anymore.
+    pub fn remove(&mut self, path: &Path) -> Result<()> {
+        self.written.retain(|written| written != path);
+        self.changed.push(path.to_owned());
+        if !self.check_only {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// The files whose content changed or that were removed, in the order they were written.
+    pub fn changed(&self) -> &[PathBuf] {
+        &self.changed
+    }
+
+    /// Finds the relative links in all written pages that point to files that don't exist,
+    /// one violation per link. Links inside code blocks are ignored, as are JSON files.
+    /// The `unwritten` files count as existing, like the pages left out by a page filter.
+    pub fn dangling_links(&self, unwritten: &[PathBuf]) -> Result<Vec<String>> {
+        let unwritten = unwritten.iter().map(|file| normalize(file)).collect::<Vec<_>>();
+        let mut dangling = Vec::new();
+        for file in self.written.iter().filter(|file| file.extension() != Some("json".as_ref())) {
+            let content = fs::read_to_string(file)
+                .wrap_err_with(|| format!("reading {} for links", file.display()))?;
+            let dir = file.parent().unwrap_or(Path::new(""));
+            let mut in_codeblock = false;
+            for line in content.lines() {
+                if line.trim_start().starts_with("```") {
+                    in_codeblock ^= true; // toggle
+                }
+                if in_codeblock {
+                    continue;
+                }
+                for link in relative_links(line) {
+                    let target = dir.join(link);
+                    if !target.exists() && !unwritten.contains(&normalize(&target)) {
+                        dangling.push(format!("{}: `{link}`", file.display()));
+                    }
+                }
+            }
+        }
+        Ok(dangling)
+    }
+
+    /// Writes a newline-delimited list of the changed files to `path`.
+    pub fn write_manifest(&self, path: &Path) -> Result<()> {
+        let manifest = self
+            .changed
+            .iter()
+            .map(|changed| format!("{}\n", changed.display()))
+            .collect::<String>();
+        fs::write(path, manifest).wrap_err("writing manifest")
+    }
+}
+
+/// Removes the `.` and `..` components of the path, without looking at the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// The targets of the markdown links in the line that are relative paths, without fragments.
+fn relative_links(line: &str) -> impl Iterator<Item = &str> {
+    line.split("](").skip(1).filter_map(|rest| {
+        let link = rest.split([')', ' ']).next()?;
+        let path = link.split('#').next()?;
+        let is_url = path.contains("://") || path.starts_with("mailto:");
+        (!path.is_empty() && !is_url).then_some(path)
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,66 @@
+#[test]
+fn manifest_lists_changed_files() {
+    let dir = std::env::temp_dir().join(format!("target-docs-manifest-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("same.md"), "same").unwrap();
+    std::fs::write(dir.join("different.md"), "old").unwrap();
+
+    let mut writer = super::OutputWriter::new(false);
+    writer.write(&dir.join("same.md"), "same").unwrap();
+    writer.write(&dir.join("different.md"), "new").unwrap();
+    writer.write(&dir.join("created.md"), "new").unwrap();
+    writer.write_manifest(&dir.join("manifest.txt")).unwrap();
+
+    let manifest = std::fs::read_to_string(dir.join("manifest.txt")).unwrap();
+    let different = std::fs::read_to_string(dir.join("different.md")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        manifest,
+        format!("{}\n{}\n", dir.join("different.md").display(), dir.join("created.md").display())
+    );
+    assert_eq!(different, "new");
+}
+
+#[test]
+fn check_only_does_not_write() {
+    let dir = std::env::temp_dir().join(format!("target-docs-check-only-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("page.md"), "old").unwrap();
+
+    let mut writer = super::OutputWriter::new(true);
+    writer.write(&dir.join("page.md"), "new").unwrap();
+
+    let page = std::fs::read_to_string(dir.join("page.md")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(writer.changed(), [dir.join("page.md")]);
+    assert_eq!(page, "old");
+}
+
+#[test]
+fn dangling_links() {
+    let dir = std::env::temp_dir().join(format!("target-docs-links-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("targets")).unwrap();
+
+    let mut writer = super::OutputWriter::new(false);
+    writer.write(&dir.join("targets").join("cat-unknown-none.md"), "# cat-unknown-none\n").unwrap();
+    writer
+        .write(
+            &dir.join("targets.md"),
+            "- [cat](targets/cat-unknown-none.md#overview)\n\
+            - [dog](targets/dog-unknown-none.md) and [docs](https://doc.rust-lang.org)\n\
+            - [top](#targets)\n\
+            ```rust\nlet x = a[..](b);\n```\n",
+        )
+        .unwrap();
+    let dangling = writer.dangling_links(&[]).unwrap();
+    let filtered = writer.dangling_links(&[dir.join("targets").join("dog-unknown-none.md")]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        dangling,
+        [format!("{}: `targets/dog-unknown-none.md`", dir.join("targets.md").display())]
+    );
+    assert!(filtered.unwrap().is_empty());
+}
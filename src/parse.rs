@@ -1,38 +1,196 @@
 //! Suboptimal half-markdown parser that's just good-enough for this.
 
 use eyre::{bail, OptionExt, Result, WrapErr};
-use serde::Deserialize;
-use std::{collections::HashMap, fs::DirEntry, path::Path};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::DirEntry,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug)]
+use crate::Progress;
+
+#[derive(Debug, Default, PartialEq)]
 pub struct ParsedTargetInfoFile {
     pub pattern: String,
     pub maintainers: Vec<String>,
     pub sections: Vec<(String, String)>,
-    pub footnotes: HashMap<String, Vec<String>>,
+    /// Maintainers of individual sections, from `<!-- maintainer: @someone -->` annotations.
+    pub section_maintainers: Vec<(String, Vec<String>)>,
+    /// Sections starting with a `<!-- draft -->` marker, which are only rendered in previews.
+    pub draft_sections: Vec<String>,
+    pub footnotes: HashMap<String, Vec<Footnote>>,
+    pub experimental: bool,
+    pub stabilized_in: Option<String>,
+    /// The tier the target info file claims, which must agree with rustc.
+    pub tier: Option<u8>,
+    /// The tier on nightly, if the targets were promoted or demoted since the last stable release.
+    pub nightly_tier: Option<u8>,
+    /// Minimum supported OS and libc versions, like `("glibc", "2.17")`.
+    pub min_versions: Vec<(String, String)>,
+    pub abi_notes: Option<String>,
+    /// Shell commands for building the target, rendered in "Building the target".
+    pub build_example: Option<String>,
+    /// Recommendations for small binaries, like `opt-level = "z"` or `panic = "abort"`.
+    pub binary_size_notes: Option<String>,
+    pub ci: Option<CiLink>,
+    pub toolchain_requirements: Vec<ToolchainRequirement>,
+    /// The RFCs and Major Change Proposals the targets were added or changed in.
+    pub proposals: Vec<Proposal>,
+    /// Names of related targets, linked to from the target pages.
+    pub see_also: Vec<String>,
+    /// Legacy names rustc also accepts for the targets.
+    pub aliases: Vec<String>,
+    /// The CI runners testing the targets.
+    pub tested_by: Vec<TestedBy>,
+    pub maintenance_status: Option<MaintenanceStatus>,
+    /// Lines of the frontmatter with trailing whitespace or tabs, which YAML handles surprisingly.
+    pub frontmatter_whitespace: Vec<(usize, WhitespaceIssue)>,
 }
 
-// IMPORTANT: This is also documented in the README, keep it in sync.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhitespaceIssue {
+    Trailing,
+    Tab,
+}
+
+// IMPORTANT: This is also documented in the README and in the JSON Schema, keep them in sync.
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 struct Frontmatter {
     #[serde(default)]
     maintainers: Vec<String>,
     #[serde(default)]
-    footnotes: HashMap<String, Vec<String>>,
+    footnotes: HashMap<String, Vec<Footnote>>,
+    #[serde(default)]
+    experimental: bool,
+    #[serde(alias = "added_in")]
+    stabilized_in: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_tier")]
+    tier: Option<u8>,
+    #[serde(default, deserialize_with = "deserialize_tier")]
+    nightly_tier: Option<u8>,
+    min_glibc: Option<String>,
+    min_musl: Option<String>,
+    min_linux_kernel: Option<String>,
+    min_macos: Option<String>,
+    min_ios: Option<String>,
+    min_windows: Option<String>,
+    abi_notes: Option<String>,
+    binary_size_notes: Option<String>,
+    build_example: Option<String>,
+    ci: Option<CiLink>,
+    #[serde(default)]
+    toolchain_requirements: Vec<ToolchainRequirement>,
+    #[serde(default)]
+    proposals: Vec<Proposal>,
+    #[serde(default)]
+    see_also: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    tested_by: Vec<TestedBy>,
+    maintenance_status: Option<MaintenanceStatus>,
+}
+
+/// A tier in the frontmatter, either a number or a quoted number like in `tier: "1"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TierValue {
+    Number(u8),
+    Quoted(String),
+}
+
+fn deserialize_tier<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<u8>, D::Error> {
+    match Option::<TierValue>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(TierValue::Number(tier)) => Ok(Some(tier)),
+        Some(TierValue::Quoted(tier)) => tier.parse().map(Some).map_err(|_| {
+            serde::de::Error::custom(format!("invalid tier `{tier}`, must be 1, 2 or 3"))
+        }),
+    }
 }
 
 // IMPORTANT: This is also documented in the README, keep it in sync.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
-pub struct TargetFootnotes {
-    pub target: String,
-    #[serde(default)]
-    pub footnotes: Vec<String>,
+pub struct CiLink {
+    pub url: String,
+    pub label: String,
+}
+
+// IMPORTANT: This is also documented in the README, keep it in sync.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolchainRequirement {
+    /// The host tool, like `LLVM` or `Xcode`.
+    pub tool: String,
+    pub min_version: String,
+}
+
+// IMPORTANT: This is also documented in the README, keep it in sync.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestedBy {
+    /// The host platform of the runner, like `x86_64 Linux`.
+    pub platform: String,
+    /// The CI runner or job name.
+    pub runner: String,
+    pub url: String,
+}
+
+// IMPORTANT: This is also documented in the README, keep it in sync.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Proposal {
+    pub title: String,
+    pub url: String,
+}
+
+// IMPORTANT: This is also documented in the README, keep it in sync.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MaintenanceStatus {
+    ActivelyMaintained,
+    PassivelyMaintained,
+    SeekingMaintainers,
+    Deprecated,
+}
+
+impl MaintenanceStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ActivelyMaintained => "actively maintained",
+            Self::PassivelyMaintained => "passively maintained",
+            Self::SeekingMaintainers => "seeking maintainers",
+            Self::Deprecated => "deprecated",
+        }
+    }
+}
+
+// IMPORTANT: This is also documented in the README, keep it in sync.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Footnote {
+    /// A footnote defined in `platform-support.md`, shared between all targets referencing it.
+    Shared(String),
+    /// A footnote defined in the frontmatter, only for this target.
+    Scoped { name: String, content: String },
+}
+
+impl Footnote {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Shared(name) | Self::Scoped { name, .. } => name,
+        }
+    }
 }
 
 // IMPORTANT: This is also documented in the README, keep it in sync.
-#[derive(Debug, PartialEq, Clone, Copy, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, clap::ValueEnum)]
 #[serde(rename_all = "snake_case")]
 pub enum TriStateBool {
     True,
@@ -40,21 +198,146 @@ pub enum TriStateBool {
     Unknown,
 }
 
-pub fn load_target_infos(directory: &Path) -> Result<Vec<ParsedTargetInfoFile>> {
-    let dir = std::fs::read_dir(directory).unwrap();
-    let mut infos = Vec::new();
+impl From<Option<bool>> for TriStateBool {
+    fn from(value: Option<bool>) -> Self {
+        match value {
+            Some(true) => Self::True,
+            Some(false) => Self::False,
+            None => Self::Unknown,
+        }
+    }
+}
 
-    for entry in dir {
-        let entry = entry?;
-        infos.push(
-            load_single_target_info(&entry)
-                .wrap_err_with(|| format!("loading {}", entry.path().display()))?,
-        )
+/// The directory next to the target info files with the notes shared by a `target_family`.
+const SHARED_NOTES_DIR: &str = "_shared";
+
+/// The sections of a `_shared/<family>.md` file, added to the pages of all targets
+/// with that `target_family`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedNotes {
+    pub family: String,
+    pub sections: Vec<(String, String)>,
+}
+
+/// Loads the shared notes from the `_shared` directory in the target info directory, if any.
+pub fn load_shared_notes(directory: &Path) -> Result<Vec<SharedNotes>> {
+    let directory = directory.join(SHARED_NOTES_DIR);
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = std::fs::read_dir(&directory)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "md"));
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let family = path.file_stem().and_then(|stem| stem.to_str());
+            let family = family.ok_or_eyre("file name is invalid utf8")?.to_owned();
+            let content = std::fs::read_to_string(path).wrap_err("reading content")?;
+            parse_shared_notes(family, &content)
+                .wrap_err_with(|| format!("loading {}", path.display()))
+        })
+        .collect()
+}
+
+fn parse_shared_notes(family: String, content: &str) -> Result<SharedNotes> {
+    // Like the target info files, they may have been saved on Windows.
+    let content = content.replace("\r\n", "\n");
+    let Sections { sections, section_maintainers, draft_sections } = parse_sections(&content, 0)?;
+    if !section_maintainers.is_empty() || !draft_sections.is_empty() {
+        bail!("shared notes can't have section maintainers or drafts");
     }
+    Ok(SharedNotes { family, sections })
+}
 
+pub fn load_target_infos(
+    directory: &Path,
+    progress: &mut Progress<impl Write>,
+) -> Result<Vec<ParsedTargetInfoFile>> {
+    let dir = std::fs::read_dir(directory).unwrap();
+    let mut entries = dir.collect::<Result<Vec<_>, _>>()?;
+    // Stray files like `.DS_Store` or editor swap files are not target infos.
+    entries.retain(|entry| {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let is_target_info = !name.starts_with('.') && name.ends_with(".md");
+        if !is_target_info && name != SHARED_NOTES_DIR {
+            let path = entry.path();
+            progress
+                .message(&format!("skipping {}, not a target info markdown file", path.display()));
+        }
+        is_target_info
+    });
+    entries.sort_by_key(|entry| entry.file_name());
+
+    // Every file is independent, so parse them on multiple threads.
+    // The chunks are joined in order, keeping the result sorted by file name.
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = entries.len().div_ceil(threads).max(1);
+
+    let results = std::thread::scope(|scope| {
+        let handles = entries
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(|| {
+                    chunk
+                        .iter()
+                        .map(|entry| {
+                            load_single_target_info(entry)
+                                .wrap_err_with(|| format!("loading {}", entry.path().display()))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("parsing thread panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    // Reports all failing files at once, so they can be fixed in one go.
+    let mut infos = Vec::new();
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(info) => infos.push(info),
+            Err(err) => errors.push(err),
+        }
+    }
+    if errors.len() == 1 {
+        return Err(errors.remove(0));
+    }
+    if !errors.is_empty() {
+        let errors = errors.iter().map(|err| format!("{err:#}")).collect::<Vec<_>>();
+        bail!("{} target info files failed to load:\n{}", errors.len(), errors.join("\n"));
+    }
+    let paths = entries.iter().map(DirEntry::path).collect::<Vec<_>>();
+    check_unique_patterns(&infos, &paths)?;
     Ok(infos)
 }
 
+/// Checks that no two files, at the same index in `paths`, define the same pattern.
+/// Otherwise the maintainers and sections of the pattern would apply twice.
+fn check_unique_patterns(infos: &[ParsedTargetInfoFile], paths: &[PathBuf]) -> Result<()> {
+    for (idx, info) in infos.iter().enumerate() {
+        if let Some(earlier) = infos[..idx].iter().position(|other| other.pattern == info.pattern) {
+            bail!(
+                "{} and {} both define the target pattern `{}`",
+                paths[earlier].display(),
+                paths[idx].display(),
+                info.pattern
+            );
+        }
+    }
+    Ok(())
+}
+
 fn load_single_target_info(entry: &DirEntry) -> Result<ParsedTargetInfoFile> {
     let pattern = entry.file_name();
     let name = pattern
@@ -68,22 +351,192 @@ fn load_single_target_info(entry: &DirEntry) -> Result<ParsedTargetInfoFile> {
 }
 
 fn parse_file(name: &str, content: &str) -> Result<ParsedTargetInfoFile> {
-    let mut frontmatter_splitter = content.split("---\n");
+    // Files saved on Windows have CRLF line endings, which would hide the `---\n` delimiters.
+    let content = content.replace("\r\n", "\n");
+
+    // Only split off the frontmatter, `---` lines in the body are horizontal rules.
+    let mut frontmatter_splitter = content.splitn(3, "---\n");
 
     let frontmatter = frontmatter_splitter.nth(1).ok_or_eyre("missing frontmatter")?;
 
     let frontmatter_line_count = frontmatter.lines().count() + 2; // 2 from ---
 
-    let frontmatter =
-        serde_yaml::from_str::<Frontmatter>(frontmatter).wrap_err("invalid frontmatter")?;
-
+    // A multi-document YAML stream is almost certainly a mistake, `serde_yaml` would only
+    // look at the first document. A plain `---` line ends the frontmatter early instead,
+    // leaving the rest of the YAML before the first heading of the body.
+    let separator_error = |line| {
+        eyre::eyre!(
+            "the frontmatter contains a YAML document separator on line {line}, \
+            it must be a single YAML document"
+        )
+    };
+    if let Some(idx) = frontmatter.lines().position(|line| line.starts_with("---")) {
+        return Err(separator_error(idx + 2));
+    }
     let body = frontmatter_splitter.next().ok_or_eyre("no body")?;
+    let before_heading = body.lines().take_while(|line| !line.starts_with('#')).collect::<Vec<_>>();
+    let continues_frontmatter =
+        before_heading.iter().find(|line| !line.trim().is_empty()).is_some_and(|l| l.contains(':'));
+    if continues_frontmatter && before_heading.contains(&"---") {
+        return Err(separator_error(frontmatter_line_count));
+    }
+
+    let frontmatter_whitespace = frontmatter
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let issue = if line.contains('\t') {
+                WhitespaceIssue::Tab
+            } else if line.ends_with(char::is_whitespace) {
+                WhitespaceIssue::Trailing
+            } else {
+                return None;
+            };
+            Some((idx + 2, issue)) // 2 because of the `---` line and 1-based line numbers
+        })
+        .collect::<Vec<_>>();
+
+    let frontmatter = serde_yaml::from_str::<Frontmatter>(frontmatter).wrap_err_with(|| {
+        // Tabs are a common reason for invalid YAML, and hard to spot.
+        match frontmatter_whitespace.iter().find(|(_, issue)| *issue == WhitespaceIssue::Tab) {
+            Some((line, _)) => format!("invalid frontmatter, line {line} contains a tab"),
+            None => "invalid frontmatter".to_owned(),
+        }
+    })?;
+
+    // The per-target entries belong to the targets this file is about.
+    let mut footnote_targets = frontmatter.footnotes.keys().collect::<Vec<_>>();
+    footnote_targets.sort();
+    for target in footnote_targets {
+        if !glob_match::glob_match(name, target) {
+            bail!("footnotes for `{target}` are outside the pattern `{name}` of this file");
+        }
+    }
+
+    if let Some(idx) = frontmatter.maintainers.iter().position(|m| m.trim().is_empty()) {
+        bail!("maintainer {} of `{name}` is empty", idx + 1);
+    }
+    for maintainer in &frontmatter.maintainers {
+        validate_team_handle(maintainer)?;
+    }
+
+    if let Some(version) = &frontmatter.stabilized_in {
+        validate_rust_version(version).wrap_err("invalid `stabilized_in`")?;
+    }
+
+    if let Some(tier) = frontmatter.tier.filter(|tier| !(1..=3).contains(tier)) {
+        bail!("invalid `tier` {tier}, must be 1, 2 or 3");
+    }
+    if let Some(tier) = frontmatter.nightly_tier.filter(|tier| !(1..=3).contains(tier)) {
+        bail!("invalid `nightly_tier` {tier}, must be 1, 2 or 3");
+    }
+
+    let min_versions = [
+        ("glibc", frontmatter.min_glibc),
+        ("musl", frontmatter.min_musl),
+        ("Linux kernel", frontmatter.min_linux_kernel),
+        ("macOS", frontmatter.min_macos),
+        ("iOS", frontmatter.min_ios),
+        ("Windows", frontmatter.min_windows),
+    ]
+    .into_iter()
+    .filter_map(|(name, version)| Some((name.to_owned(), version?)))
+    .collect::<Vec<_>>();
+    for (name, version) in &min_versions {
+        validate_version(version).wrap_err_with(|| format!("invalid minimum {name} version"))?;
+    }
+
+    if frontmatter.build_example.as_ref().is_some_and(|example| example.trim().is_empty()) {
+        bail!("`build_example` must not be empty");
+    }
+
+    if let Some(ci) = &frontmatter.ci {
+        validate_url(&ci.url).wrap_err("invalid `ci.url`")?;
+    }
+
+    for (idx, requirement) in frontmatter.toolchain_requirements.iter().enumerate() {
+        let tool = requirement.tool.trim();
+        if tool.is_empty() {
+            bail!("`toolchain_requirements` entry {} has an empty `tool`", idx + 1);
+        }
+        if frontmatter.toolchain_requirements[..idx].iter().any(|other| other.tool.trim() == tool) {
+            bail!("`toolchain_requirements` lists `{tool}` more than once");
+        }
+        validate_version(&requirement.min_version)
+            .wrap_err_with(|| format!("invalid minimum {tool} version"))?;
+    }
+
+    for tested_by in &frontmatter.tested_by {
+        if tested_by.platform.trim().is_empty() || tested_by.runner.trim().is_empty() {
+            bail!("`tested_by` entry `{}` needs a `platform` and a `runner`", tested_by.url);
+        }
+        validate_url(&tested_by.url).wrap_err_with(|| {
+            format!("invalid `url` of `tested_by` runner `{}`", tested_by.runner)
+        })?;
+    }
+
+    for proposal in &frontmatter.proposals {
+        if proposal.title.trim().is_empty() {
+            bail!("proposal `{}` has an empty `title`", proposal.url);
+        }
+        validate_url(&proposal.url)
+            .wrap_err_with(|| format!("invalid `url` of proposal `{}`", proposal.title))?;
+    }
+
+    let Sections { sections, section_maintainers, draft_sections } =
+        parse_sections(body, frontmatter_line_count)?;
+
+    for maintainer in section_maintainers.iter().flat_map(|(_, maintainers)| maintainers) {
+        validate_team_handle(maintainer)?;
+    }
+
+    // The overview is the first thing readers see, an empty one is worse than the stub.
+    if sections.iter().any(|(name, content)| name == "Overview" && content.is_empty()) {
+        bail!("the Overview section of `{name}` is empty");
+    }
+
+    Ok(ParsedTargetInfoFile {
+        pattern: name.to_owned(),
+        maintainers: frontmatter.maintainers,
+        sections,
+        section_maintainers,
+        draft_sections,
+        footnotes: frontmatter.footnotes,
+        experimental: frontmatter.experimental,
+        stabilized_in: frontmatter.stabilized_in,
+        tier: frontmatter.tier,
+        nightly_tier: frontmatter.nightly_tier,
+        min_versions,
+        abi_notes: frontmatter.abi_notes,
+        binary_size_notes: frontmatter.binary_size_notes,
+        build_example: frontmatter.build_example,
+        ci: frontmatter.ci,
+        toolchain_requirements: frontmatter.toolchain_requirements,
+        proposals: frontmatter.proposals,
+        see_also: frontmatter.see_also,
+        aliases: frontmatter.aliases,
+        tested_by: frontmatter.tested_by,
+        maintenance_status: frontmatter.maintenance_status,
+        frontmatter_whitespace,
+    })
+}
+
+/// The `## ` sections of a markdown body.
+struct Sections {
+    sections: Vec<(String, String)>,
+    section_maintainers: Vec<(String, Vec<String>)>,
+    draft_sections: Vec<String>,
+}
 
+/// Parses the sections of a markdown body that starts after line `line_offset` of the file.
+fn parse_sections(body: &str, line_offset: usize) -> Result<Sections> {
     let mut sections = Vec::<(String, String)>::new();
+    let mut section_maintainers = Vec::<(String, Vec<String>)>::new();
+    let mut draft_sections = Vec::new();
     let mut in_codeblock = false;
 
     for (idx, line) in body.lines().enumerate() {
-        let number = frontmatter_line_count + idx + 1; // 1 because "line numbers" are off by 1
+        let number = line_offset + idx + 1; // 1 because "line numbers" are off by 1
 
         let push_line = |sections: &mut Vec<(String, String)>, line| {
             match sections.last_mut() {
@@ -106,16 +559,36 @@ fn parse_file(name: &str, content: &str) -> Result<ParsedTargetInfoFile> {
             if in_codeblock {
                 push_line(&mut sections, line)?;
             } else if let Some(header) = line.strip_prefix("## ") {
-                if !crate::SECTIONS.contains(&header) {
+                if crate::section_kind(header).is_none() {
                     bail!(
                         "on line {number}, `{header}` is not an allowed section name, must be one of {:?}",
-                        super::SECTIONS
+                        crate::section_names()
                     );
                 }
                 sections.push((header.to_owned(), String::new()));
+            } else if line.starts_with("###") {
+                // Subsections are part of the content of their section.
+                push_line(&mut sections, line)?;
             } else {
-                bail!("on line {number}, the only allowed headings are `## `: `{line}`");
+                bail!(
+                    "on line {number}, the only allowed headings are `## ` and subsections: `{line}`"
+                );
+            }
+        } else if let Some(maintainer) = parse_section_maintainer(line).filter(|_| !in_codeblock) {
+            let Some((section_name, _)) = sections.last() else {
+                bail!("on line {number}, section maintainer annotation found before the first heading");
+            };
+            match section_maintainers.iter_mut().find(|(name, _)| name == section_name) {
+                Some((_, maintainers)) => maintainers.push(maintainer.to_owned()),
+                None => {
+                    section_maintainers.push((section_name.clone(), vec![maintainer.to_owned()]))
+                }
             }
+        } else if line.trim() == "<!-- draft -->"
+            && sections.last().is_some_and(|(_, content)| content.trim().is_empty())
+        {
+            let (section_name, _) = sections.last().unwrap();
+            draft_sections.push(section_name.clone());
         } else {
             push_line(&mut sections, line)?;
         }
@@ -123,13 +596,105 @@ fn parse_file(name: &str, content: &str) -> Result<ParsedTargetInfoFile> {
 
     sections.iter_mut().for_each(|section| section.1 = section.1.trim().to_owned());
 
-    Ok(ParsedTargetInfoFile {
-        pattern: name.to_owned(),
-        maintainers: frontmatter.maintainers,
-        sections,
-        footnotes: frontmatter.footnotes,
-    })
+    Ok(Sections { sections, section_maintainers, draft_sections })
+}
+
+/// Warns about tabs and trailing whitespace in the frontmatter.
+pub fn frontmatter_whitespace_warnings(info: &ParsedTargetInfoFile) -> Vec<String> {
+    info.frontmatter_whitespace
+        .iter()
+        .map(|(line, issue)| {
+            let issue = match issue {
+                WhitespaceIssue::Trailing => "has trailing whitespace",
+                WhitespaceIssue::Tab => "contains a tab",
+            };
+            format!("in `{}`, line {line} of the frontmatter {issue}", info.pattern)
+        })
+        .collect()
 }
 
+/// How many lines at the top of a section are checked for an accidentally pasted frontmatter.
+const PASTED_FRONTMATTER_LINES: usize = 5;
+
+/// Warns about sections with a `---` line after a blank line near their top,
+/// which usually means that a full target info file including its frontmatter was pasted in.
+pub fn pasted_frontmatter_warnings(info: &ParsedTargetInfoFile) -> Vec<String> {
+    info.sections
+        .iter()
+        .filter(|(_, content)| {
+            let lines = content.lines().take(PASTED_FRONTMATTER_LINES);
+            let lines = lines.take_while(|line| !line.starts_with("```")).collect::<Vec<_>>();
+            lines
+                .iter()
+                .enumerate()
+                .any(|(idx, line)| *line == "---" && (idx == 0 || lines[idx - 1].trim().is_empty()))
+        })
+        .map(|(name, _)| {
+            format!(
+                "in `{}`, section {name} looks like it contains a pasted frontmatter",
+                info.pattern
+            )
+        })
+        .collect()
+}
+
+/// Validates that a URL is an absolute `https://` URL.
+fn validate_url(url: &str) -> Result<()> {
+    let Some(rest) = url.strip_prefix("https://") else {
+        bail!("`{url}` must start with `https://`");
+    };
+    if rest.is_empty() || rest.starts_with('/') || url.contains(char::is_whitespace) {
+        bail!("`{url}` is not a valid URL");
+    }
+    Ok(())
+}
+
+/// Validates that a version consists of up to four numbers separated by dots, like `2.17`.
+fn validate_version(version: &str) -> Result<()> {
+    let parts = version.split('.').collect::<Vec<_>>();
+    let is_number = |part: &&str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if parts.len() > 4 || !parts.iter().all(is_number) {
+        bail!("`{version}` is not a version like `2.17`");
+    }
+    Ok(())
+}
+
+/// Validates that a version is a Rust release like `1.74` or `1.74.1`.
+fn validate_rust_version(version: &str) -> Result<()> {
+    let parts = version.split('.').collect::<Vec<_>>();
+    let is_number = |part: &&str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if !(2..=3).contains(&parts.len()) || parts[0] != "1" || !parts.iter().all(is_number) {
+        bail!("`{version}` is not a Rust version like `1.74` or `1.74.1`");
+    }
+    Ok(())
+}
+
+/// Checks that a `@org/team` maintainer names both the organization and the team,
+/// so it can be linked to the team page.
+fn validate_team_handle(maintainer: &str) -> Result<()> {
+    let Some(handle) = maintainer.strip_prefix('@').filter(|handle| !handle.contains(' ')) else {
+        return Ok(());
+    };
+    if let Some((org, team)) = handle.split_once('/') {
+        if org.is_empty() || team.is_empty() || team.contains('/') {
+            bail!("maintainer `{maintainer}` is not a valid team handle, must be `@org/team`");
+        }
+    }
+    Ok(())
+}
+
+/// Parses a `<!-- maintainer: @someone -->` annotation line, returning the maintainer.
+fn parse_section_maintainer(line: &str) -> Option<&str> {
+    let maintainer = line
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix("maintainer:")?;
+    Some(maintainer.trim()).filter(|maintainer| !maintainer.is_empty())
+}
+
+pub mod schema;
+
 #[cfg(test)]
 mod tests;
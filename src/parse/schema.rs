@@ -0,0 +1,102 @@
+//! A JSON Schema of the frontmatter of the target info files, for validating them in editors.
+
+use serde_json::{json, Value};
+
+// IMPORTANT: This describes `Frontmatter` and the types in it, keep it in sync.
+/// The JSON Schema of the frontmatter. Like the parser, it rejects unknown keys.
+pub fn frontmatter_schema() -> Value {
+    let string = json!({ "type": "string" });
+    let text = |description: &str| json!({ "type": "string", "description": description });
+    // Like in the README example, the tier may be quoted.
+    let tier =
+        |description: &str| json!({ "enum": [1, 2, 3, "1", "2", "3"], "description": description });
+    let link = |fields: &[&str], description: &str| {
+        let properties = fields.iter().map(|field| (field.to_string(), string.clone()));
+        json!({
+            "type": "object",
+            "description": description,
+            "properties": properties.collect::<serde_json::Map<_, _>>(),
+            "required": fields,
+            "additionalProperties": false,
+        })
+    };
+    let footnote = json!({
+        "oneOf": [
+            { "type": "string", "description": "A footnote defined in platform-support.md." },
+            {
+                "description": "A footnote only for this target.",
+                "type": "object",
+                "properties": { "name": string, "content": string },
+                "required": ["name", "content"],
+                "additionalProperties": false,
+            },
+        ]
+    });
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Target info frontmatter",
+        "type": "object",
+        "properties": {
+            "tier": tier("The tier of the targets."),
+            "maintainers": {
+                "type": "array",
+                "description": "GitHub `@handle`s, `@org/team`s, emails or other names.",
+                "items": { "type": "string", "minLength": 1 },
+            },
+            "stabilized_in": text("The Rust version the targets were added in, like `1.74`."),
+            "added_in": text("Another name for `stabilized_in`."),
+            "nightly_tier": tier("The tier on nightly, if it differs from the stable release."),
+            "min_glibc": text("The minimum supported glibc version."),
+            "min_musl": text("The minimum supported musl version."),
+            "min_linux_kernel": text("The minimum supported Linux kernel version."),
+            "min_macos": text("The minimum supported macOS version."),
+            "min_ios": text("The minimum supported iOS version."),
+            "min_windows": text("The minimum supported Windows version."),
+            "toolchain_requirements": {
+                "type": "array",
+                "items": link(&["tool", "min_version"], "A host tool the targets need."),
+            },
+            "abi_notes": text("Notes about the ABI and calling conventions."),
+            "build_example": text("Shell commands for building the targets."),
+            "binary_size_notes": text("Recommendations for small binaries."),
+            "ci": link(&["url", "label"], "A link to the CI job testing the targets."),
+            "proposals": {
+                "type": "array",
+                "items": link(&["title", "url"], "An RFC or Major Change Proposal."),
+            },
+            "see_also": {
+                "type": "array",
+                "description": "Names of related targets.",
+                "items": string,
+            },
+            "aliases": {
+                "type": "array",
+                "description": "Legacy names rustc also accepts for the targets.",
+                "items": string,
+            },
+            "tested_by": {
+                "type": "array",
+                "items": link(&["platform", "runner", "url"], "A CI runner testing the targets."),
+            },
+            "maintenance_status": {
+                "enum": [
+                    "actively-maintained",
+                    "passively-maintained",
+                    "seeking-maintainers",
+                    "deprecated",
+                ],
+            },
+            "experimental": {
+                "type": "boolean",
+                "description": "Whether the targets are experimental even within their tier.",
+            },
+            "footnotes": {
+                "type": "object",
+                "description": "The footnotes of the targets in the tables, by target name.",
+                "additionalProperties": { "type": "array", "items": footnote },
+            },
+        },
+        "additionalProperties": false,
+    })
+}
@@ -31,6 +31,35 @@ fn wrong_header() {
     assert!(super::parse_file(name, content).is_err());
 }
 
+#[test]
+fn subsections() {
+    let name = "cat-unknown-linux-gnu.md";
+    let content = "
+---
+---
+## Requirements
+
+### Food
+
+Fish.
+
+#### Water
+
+Fresh.
+";
+
+    let info = super::parse_file(name, content).unwrap();
+    assert_eq!(
+        info.sections,
+        [("Requirements".to_owned(), "### Food\n\nFish.\n\n#### Water\n\nFresh.".to_owned())]
+    );
+
+    let before_heading = "\n---\n---\n### Food\n";
+    assert!(super::parse_file(name, before_heading).is_err());
+    let unknown_section = "\n---\n---\n## Food\n\n### Fish\n";
+    assert!(super::parse_file(name, unknown_section).is_err());
+}
+
 #[test]
 fn parse_correctly() {
     let name = "cat-unknown-linux-gnu.md";
@@ -97,3 +126,443 @@ fn backticks() {
         vec![("Testing".to_owned(), "```text\n# hello world\n```".to_owned(),),]
     );
 }
+
+#[test]
+fn section_maintainer() {
+    let name = "cat-unknown-linux-gnu";
+    let content = r#"
+---
+maintainers: ["@overall"]
+---
+## Requirements
+
+Some fish.
+
+## Testing
+<!-- maintainer: @tester -->
+
+Pet the cat.
+    "#;
+
+    let info = super::parse_file(name, content).unwrap();
+
+    assert_eq!(info.maintainers, vec!["@overall"]);
+    assert_eq!(info.section_maintainers, vec![("Testing".to_owned(), vec!["@tester".to_owned()])]);
+    assert_eq!(
+        info.sections,
+        vec![
+            ("Requirements".to_owned(), "Some fish.".to_owned()),
+            ("Testing".to_owned(), "Pet the cat.".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn parallel_loading_is_sorted() {
+    let dir = std::env::temp_dir().join(format!("target-docs-parallel-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let names = (0..20).map(|i| format!("target{i:02}-unknown-none")).collect::<Vec<_>>();
+    for name in names.iter().rev() {
+        let content = format!("---\nmaintainers: [\"@{name}\"]\n---\n## Testing\n{name}\n");
+        std::fs::write(dir.join(format!("{name}.md")), content).unwrap();
+    }
+
+    let infos = super::load_target_infos(&dir, &mut crate::Progress::new(true, std::io::sink()));
+    let infos = infos.unwrap();
+
+    let sequential = names
+        .iter()
+        .map(|name| {
+            let content = std::fs::read_to_string(dir.join(format!("{name}.md"))).unwrap();
+            super::parse_file(name, &content).unwrap()
+        })
+        .collect::<Vec<_>>();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(infos, sequential);
+}
+
+#[test]
+fn stabilized_in() {
+    let parse = |version: &str| {
+        let content = format!("---\nstabilized_in: \"{version}\"\n---\n");
+        super::parse_file("cat-unknown-linux-gnu", &content)
+    };
+
+    assert_eq!(parse("1.74").unwrap().stabilized_in.as_deref(), Some("1.74"));
+    assert_eq!(parse("1.74.1").unwrap().stabilized_in.as_deref(), Some("1.74.1"));
+    assert!(parse("1.74.1.0").is_err());
+    assert!(parse("v1.74").is_err());
+    assert!(parse("1.").is_err());
+    assert!(parse("2.0").is_err());
+
+    let added_in = super::parse_file("cat-unknown-linux-gnu", "---\nadded_in: \"1.74\"\n---\n");
+    assert_eq!(added_in.unwrap().stabilized_in.as_deref(), Some("1.74"));
+    let both = "---\nadded_in: \"1.74\"\nstabilized_in: \"1.74\"\n---\n";
+    assert!(super::parse_file("cat-unknown-linux-gnu", both).is_err());
+}
+
+#[test]
+fn maintenance_status() {
+    let parse = |status: &str| {
+        let content = format!("---\nmaintenance_status: {status}\n---\n");
+        super::parse_file("cat-unknown-linux-gnu", &content)
+    };
+
+    let status = parse("seeking-maintainers").unwrap().maintenance_status;
+    assert_eq!(status, Some(super::MaintenanceStatus::SeekingMaintainers));
+    assert!(parse("unmaintained").is_err());
+}
+
+#[test]
+fn footnotes() {
+    let content = r#"
+---
+footnotes:
+  i686-pc-windows-gnu:
+    - "x86_32-floats-return-ABI"
+    - name: "1"
+      content: "Only Windows 10 is tested."
+---
+"#;
+
+    let info = super::parse_file("i686-pc-windows-*", content).unwrap();
+
+    assert_eq!(
+        info.footnotes["i686-pc-windows-gnu"],
+        vec![
+            super::Footnote::Shared("x86_32-floats-return-ABI".to_owned()),
+            super::Footnote::Scoped {
+                name: "1".to_owned(),
+                content: "Only Windows 10 is tested.".to_owned()
+            },
+        ]
+    );
+}
+
+#[test]
+fn min_versions() {
+    let content = "---\nmin_glibc: \"2.17\"\nmin_linux_kernel: \"3.2\"\n---\n";
+    let info = super::parse_file("x86_64-unknown-linux-gnu", content).unwrap();
+    assert_eq!(
+        info.min_versions,
+        vec![
+            ("glibc".to_owned(), "2.17".to_owned()),
+            ("Linux kernel".to_owned(), "3.2".to_owned())
+        ]
+    );
+
+    let content = "---\nmin_glibc: \"two point seventeen\"\n---\n";
+    assert!(super::parse_file("x86_64-unknown-linux-gnu", content).is_err());
+}
+
+#[test]
+fn invalid_toolchain_requirements() {
+    for requirements in [
+        r#"[{ tool: "", min_version: "17" }]"#,
+        r#"[{ tool: "LLVM", min_version: "seventeen" }]"#,
+        r#"[{ tool: "LLVM", min_version: "17" }, { tool: "LLVM", min_version: "18" }]"#,
+    ] {
+        let content = format!("---\ntoolchain_requirements: {requirements}\n---\n");
+        assert!(super::parse_file("aarch64-apple-ios", &content).is_err(), "{requirements}");
+    }
+}
+
+#[test]
+fn empty_maintainer() {
+    let content = "---\nmaintainers: [\"\", \"@real\"]\n---\n";
+    let err = super::parse_file("cat-unknown-linux-gnu", content).unwrap_err();
+    assert_eq!(err.to_string(), "maintainer 1 of `cat-unknown-linux-gnu` is empty");
+
+    let content = "---\nmaintainers: [\"@real\", \"  \"]\n---\n";
+    assert!(super::parse_file("cat-unknown-linux-gnu", content).is_err());
+}
+
+#[test]
+fn team_handles() {
+    let content = "---\nmaintainers: [\"@rust-lang/opsem\", \"Some One\"]\n---\n";
+    assert!(super::parse_file("cat-unknown-linux-gnu", content).is_ok());
+
+    for team in ["@rust-lang/", "@/opsem", "@rust-lang/opsem/x"] {
+        let content = format!("---\nmaintainers: [\"{team}\"]\n---\n");
+        let err = super::parse_file("cat-unknown-linux-gnu", &content).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("maintainer `{team}` is not a valid team handle, must be `@org/team`")
+        );
+    }
+
+    let content = "---\n---\n## Testing\n<!-- maintainer: @rust-lang/ -->\n";
+    assert!(super::parse_file("cat-unknown-linux-gnu", content).is_err());
+}
+
+#[test]
+fn invalid_proposal_url() {
+    let content = "---\nproposals: [{ title: \"MCP\", url: \"http://example.com\" }]\n---\n";
+    assert!(super::parse_file("cat-unknown-none", content).is_err());
+}
+
+#[test]
+fn empty_overview() {
+    let parse = |overview: &str| {
+        let content = format!("---\n---\n## Overview\n{overview}\n## Testing\nCI.\n");
+        super::parse_file("cat-unknown-none", &content)
+    };
+
+    let err = parse("  \n").unwrap_err();
+    assert_eq!(err.to_string(), "the Overview section of `cat-unknown-none` is empty");
+    assert_eq!(parse("A cat.").unwrap().sections[0].1, "A cat.");
+}
+
+#[test]
+fn frontmatter_whitespace() {
+    let content = "---\nmaintainers: [\"@cat\"] \nexperimental: true\nfootnotes:\n\tcat-unknown-none: []\n---\n";
+    let err = super::parse_file("cat-unknown-none", content).unwrap_err();
+    assert_eq!(err.to_string(), "invalid frontmatter, line 5 contains a tab");
+
+    let content = "---\nmaintainers: [\"@cat\"] \nexperimental: true\n---\n## Overview\nA cat.  \n";
+    let info = super::parse_file("cat-unknown-none", content).unwrap();
+    assert_eq!(
+        super::frontmatter_whitespace_warnings(&info),
+        ["in `cat-unknown-none`, line 2 of the frontmatter has trailing whitespace"]
+    );
+}
+
+#[test]
+fn shared_notes() {
+    let notes = super::parse_shared_notes("wasm".to_owned(), "## Overview\nNo threads.\n").unwrap();
+    assert_eq!(notes.sections, [("Overview".to_owned(), "No threads.".to_owned())]);
+
+    let content = "## Overview\n<!-- maintainer: @wasm -->\nNo threads.\n";
+    assert!(super::parse_shared_notes("wasm".to_owned(), content).is_err());
+}
+
+#[test]
+fn draft_section() {
+    let content = "---\n---\n## Overview\n<!-- draft -->\nWork in progress.\n## Testing\nCI.\n<!-- draft -->\n";
+    let info = super::parse_file("cat-unknown-none", content).unwrap();
+    assert_eq!(info.draft_sections, vec!["Overview"]);
+    assert_eq!(
+        info.sections,
+        vec![
+            ("Overview".to_owned(), "Work in progress.".to_owned()),
+            ("Testing".to_owned(), "CI.\n<!-- draft -->".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn footnotes_outside_pattern() {
+    let content = "---\nfootnotes:\n  x86_64-unknown-linux-gnu: [\"glibc\"]\n---\n";
+    assert!(super::parse_file("*-linux-*", content).is_ok());
+
+    let content = "---\nfootnotes:\n  x86_64-pc-windows-msvc: [\"msvc\"]\n---\n";
+    let err = super::parse_file("*-linux-*", content).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "footnotes for `x86_64-pc-windows-msvc` are outside the pattern `*-linux-*` of this file"
+    );
+}
+
+#[test]
+fn pasted_frontmatter() {
+    let content = "---\n---\n## Overview\nA cat.\n\n---\nmaintainers: [\"@cat\"]\n---\n\
+        ## Testing\n```yaml\n\n---\n```\n## Requirements\nFood.\n---\n";
+    let info = super::parse_file("cat-unknown-none", content).unwrap();
+
+    assert_eq!(
+        super::pasted_frontmatter_warnings(&info),
+        vec!["in `cat-unknown-none`, section Overview looks like it contains a pasted frontmatter"]
+    );
+}
+
+#[test]
+fn empty_build_example() {
+    let content = "---\nbuild_example: \"  \"\n---\n";
+    assert!(super::parse_file("wasm32-wasip1", content).is_err());
+}
+
+#[test]
+fn multi_document_frontmatter() {
+    let content =
+        "---\nmaintainers: [\"@cat\"]\n---\nexperimental: true\n---\n## Overview\nA cat.\n";
+    let err = super::parse_file("cat-unknown-none", content).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the frontmatter contains a YAML document separator on line 3, it must be a single YAML document"
+    );
+
+    let content = "---\nmaintainers: [\"@cat\"]\n--- # more\nexperimental: true\n---\n";
+    let err = super::parse_file("cat-unknown-none", content).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("the frontmatter contains a YAML document separator on line 3"));
+}
+
+#[test]
+fn invalid_tested_by() {
+    for tested_by in [
+        r#"{ platform: "", runner: "x86_64-gnu", url: "https://example.com" }"#,
+        r#"{ platform: "x86_64 Linux", runner: "x86_64-gnu", url: "example.com" }"#,
+    ] {
+        let content = format!("---\ntested_by: [{tested_by}]\n---\n");
+        assert!(super::parse_file("x86_64-unknown-linux-gnu", &content).is_err(), "{tested_by}");
+    }
+}
+
+#[test]
+fn skip_non_markdown_files() {
+    let dir = std::env::temp_dir().join(format!("target-docs-skip-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("cat-unknown-linux-gnu.md"), "---\n---\n").unwrap();
+    std::fs::write(dir.join(".DS_Store"), [0, 0, 0, 1]).unwrap();
+    std::fs::write(dir.join(".cat-unknown-linux-gnu.md.swp"), "").unwrap();
+
+    let mut progress = crate::Progress::new(false, Vec::new());
+    let infos = super::load_target_infos(&dir, &mut progress);
+
+    std::fs::write(dir.join("dog-unknown-linux-gnu.md"), "no frontmatter").unwrap();
+    let malformed = super::load_target_infos(&dir, &mut crate::Progress::new(true, Vec::new()));
+    std::fs::write(dir.join("fox-unknown-linux-gnu.md"), "---\nnope: 1\n---\n").unwrap();
+    let all_malformed =
+        super::load_target_infos(&dir, &mut crate::Progress::new(true, Vec::new())).unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let patterns = infos.unwrap().into_iter().map(|info| info.pattern).collect::<Vec<_>>();
+    assert_eq!(patterns, vec!["cat-unknown-linux-gnu"]);
+    let messages = String::from_utf8(progress.out.unwrap()).unwrap();
+    assert!(messages.contains(".DS_Store, not a target info markdown file\n"));
+    assert!(messages.contains(".cat-unknown-linux-gnu.md.swp, not a target info markdown file\n"));
+    assert!(malformed.is_err());
+    let all_malformed = all_malformed.to_string();
+    assert!(all_malformed.starts_with("2 target info files failed to load:\n"));
+    assert!(all_malformed.contains("dog-unknown-linux-gnu.md: missing frontmatter"));
+    assert!(all_malformed.contains("fox-unknown-linux-gnu.md: invalid frontmatter: unknown field"));
+}
+
+#[test]
+fn horizontal_rule() {
+    let content = "---\n---\n## Requirements\n\nAbove.\n\n---\n\nBelow.\n\n## Testing\n\nTested.\n";
+
+    let info = super::parse_file("cat-unknown-linux-gnu", content).unwrap();
+
+    assert_eq!(
+        info.sections,
+        vec![
+            ("Requirements".to_owned(), "Above.\n\n---\n\nBelow.".to_owned()),
+            ("Testing".to_owned(), "Tested.".to_owned()),
+        ]
+    );
+
+    let target =
+        crate::TargetInfo { name: info.pattern, sections: info.sections, ..Default::default() };
+    assert!(crate::render::render_target_md(&target, &Default::default())
+        .contains("## Requirements\nAbove.\n\n---\n\nBelow.\n\n## Testing"));
+}
+
+#[test]
+fn ci_link() {
+    let parse = |url: &str| {
+        let content = format!("---\nci:\n  url: \"{url}\"\n  label: \"dist-x86_64-linux\"\n---\n");
+        super::parse_file("x86_64-unknown-linux-gnu", &content)
+    };
+
+    let info = parse("https://github.com/rust-lang/rust/actions").unwrap();
+    assert_eq!(
+        info.ci,
+        Some(super::CiLink {
+            url: "https://github.com/rust-lang/rust/actions".to_owned(),
+            label: "dist-x86_64-linux".to_owned(),
+        })
+    );
+    assert!(parse("http://example.com").is_err());
+    assert!(parse("https://").is_err());
+    assert!(parse("https://example.com/a b").is_err());
+}
+
+#[test]
+fn serialize_round_trip() {
+    use super::{Footnote, TriStateBool};
+
+    for value in [TriStateBool::True, TriStateBool::False, TriStateBool::Unknown] {
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<TriStateBool>(&json).unwrap(), value);
+    }
+    assert_eq!(serde_json::to_string(&TriStateBool::Unknown).unwrap(), r#""unknown""#);
+
+    let footnotes = vec![
+        Footnote::Shared("glibc".to_owned()),
+        Footnote::Scoped { name: "windows".to_owned(), content: "Only Windows 10.".to_owned() },
+    ];
+    let json = serde_json::to_string(&footnotes).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<Footnote>>(&json).unwrap(), footnotes);
+}
+
+#[test]
+fn crlf_line_endings() {
+    let content =
+        "---\nmaintainers: [\"@cat\"]\n---\n## Overview\nMeow.\n\n## Testing\nPet the cat.\n";
+    let lf = super::parse_file("cat-unknown-linux-gnu", content).unwrap();
+    let crlf = super::parse_file("cat-unknown-linux-gnu", &content.replace('\n', "\r\n")).unwrap();
+
+    assert_eq!(crlf.maintainers, ["@cat"]);
+    assert_eq!(crlf.sections, lf.sections);
+    assert!(crlf.frontmatter_whitespace.is_empty());
+
+    let content = "## Overview\nNo threads.\n\n## Testing\nIn a browser.\n";
+    let lf = super::parse_shared_notes("wasm".to_owned(), content).unwrap();
+    let crlf = super::parse_shared_notes("wasm".to_owned(), &content.replace('\n', "\r\n"));
+    assert_eq!(crlf.unwrap(), lf);
+}
+
+#[test]
+fn schema_lists_all_frontmatter_keys() {
+    // The error for an unknown key lists all the keys serde knows about.
+    let err = serde_yaml::from_str::<super::Frontmatter>("not_a_key: 1").err().unwrap();
+    let expected = err.to_string();
+    let (_, expected) = expected.split_once("expected one of ").unwrap();
+    let mut expected = expected
+        .split(", ")
+        .map(|key| key.split('`').nth(1).unwrap().to_owned())
+        .collect::<Vec<_>>();
+    expected.sort();
+
+    let schema = super::schema::frontmatter_schema();
+    let mut keys = schema["properties"].as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, expected);
+    assert_eq!(schema["additionalProperties"], false);
+}
+
+#[test]
+fn duplicate_patterns() {
+    let info = |pattern: &str| super::ParsedTargetInfoFile {
+        pattern: pattern.to_owned(),
+        ..Default::default()
+    };
+    let infos = [info("*-linux-gnu"), info("*-apple-*"), info("*-linux-gnu")];
+    let paths =
+        ["a/*-linux-gnu.md", "*-apple-*.md", "b/*-linux-gnu.md"].map(std::path::PathBuf::from);
+
+    assert!(super::check_unique_patterns(&infos[..2], &paths[..2]).is_ok());
+    assert_eq!(
+        super::check_unique_patterns(&infos, &paths).unwrap_err().to_string(),
+        "a/*-linux-gnu.md and b/*-linux-gnu.md both define the target pattern `*-linux-gnu`"
+    );
+}
+
+#[test]
+fn quoted_tier() {
+    let parse = |tier: &str| {
+        let content = format!("---\ntier: {tier}\nnightly_tier: {tier}\n---\n");
+        super::parse_file("cat-unknown-linux-gnu", &content)
+    };
+
+    // The form of the example in the README.
+    let quoted = parse("\"1\"").unwrap();
+    assert_eq!((quoted.tier, quoted.nightly_tier), (Some(1), Some(1)));
+    assert_eq!(parse("2").unwrap().tier, Some(2));
+    assert!(parse("\"one\"").is_err());
+    assert!(parse("\"4\"").is_err());
+    assert!(parse("4").is_err());
+}
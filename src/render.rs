@@ -1,39 +1,291 @@
-use eyre::{Context, OptionExt, Result};
-use std::{fs, path::Path};
+use eyre::{bail, Context, OptionExt, Result};
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
 
-use crate::TargetInfo;
+use crate::{
+    output::OutputWriter,
+    parse::{Footnote, MaintenanceStatus, ParsedTargetInfoFile},
+    TargetInfo,
+};
+
+/// Options controlling how the output is rendered.
+#[derive(Default)]
+pub struct RenderOptions {
+    /// Truncate the notes in the platform support tables to this many characters.
+    /// The full notes are still rendered on the target page.
+    pub max_notes_len: Option<usize>,
+    /// Generate a landing page for every `target_arch`.
+    pub arch_pages: bool,
+    /// Replaces the message on the pages of targets without maintainers.
+    pub no_maintainers_message: Option<String>,
+    /// Render the message for targets without maintainers as a warning alert.
+    pub no_maintainers_alert: bool,
+    /// Only a subset of the targets is rendered, which the tables note.
+    pub partial: bool,
+    /// Render sections marked as `<!-- draft -->` instead of stubbing them out.
+    pub include_drafts: bool,
+    /// Render the minimum versions, toolchain requirements and the listed items of the
+    /// Requirements section as a single checklist in the Requirements section.
+    pub checklist_requirements: bool,
+    /// File extension of the target pages, `md` by default.
+    pub page_extension: Option<String>,
+    /// Where the tiers on the target pages link to, [`TIER_POLICY_URL`] by default.
+    pub tier_policy_url: Option<String>,
+    /// Split the tier tables with more rows than this into one table per `target_arch`.
+    pub split_tables_over: Option<usize>,
+    /// Collapse cfg lists with more entries than this, [`CFG_COLLAPSE_THRESHOLD`] by default.
+    pub cfg_collapse_threshold: Option<usize>,
+    /// URL of the rendered book, for canonical URLs and absolute links between the pages.
+    pub base_url: Option<String>,
+    /// Show the GitHub avatars of the maintainers, as inline HTML.
+    pub maintainer_avatars: bool,
+    /// Also write the information about all targets to `targets.json`, for other tools.
+    pub json: bool,
+    /// Render the cfg values as one list, instead of grouped into [`CFG_GROUPS`].
+    pub flat_cfg: bool,
+}
+
+impl RenderOptions {
+    pub fn page_extension(&self) -> &str {
+        self.page_extension.as_deref().map_or("md", |extension| extension.trim_start_matches('.'))
+    }
+}
+
+/// The target tier policy, relative to the target pages in `platform-support/targets`.
+const TIER_POLICY_URL: &str = "../../target-tier-policy.md";
+
+/// The content of sections without information.
+const STUB: &str = "Unknown.";
+
+/// The URL of a page in the book rendered by mdBook at `base_url`, given its path in `src`.
+fn page_url(base_url: &str, src_path: &str) -> String {
+    let path =
+        src_path.strip_suffix(".md").map_or(src_path.to_owned(), |path| format!("{path}.html"));
+    format!("{}/{path}", base_url.trim_end_matches('/'))
+}
+
+/// The canonical URL of the page of a target, if the URL of the book is known.
+pub fn canonical_url(target: &str, options: &RenderOptions) -> Option<String> {
+    let base_url = options.base_url.as_deref()?;
+    Some(page_url(base_url, &target_page_src_path(target, options)))
+}
+
+/// Longer cfg lists are collapsed into a `<details>` block, they would dominate the page.
+const CFG_COLLAPSE_THRESHOLD: usize = 12;
 
 /// Renders a single target markdown file from the information obtained.
-pub fn render_target_md(target: &TargetInfo) -> String {
+pub fn render_target_md(target: &TargetInfo, options: &RenderOptions) -> String {
     let render_header_option_bool = |bool| match bool {
         Some(true) => "Yes",
         Some(false) => "No",
         None => "?",
     };
 
-    let mut doc = format!(
-        "# {}\n\n**Tier: {}**\n\n**std: {}**\n\n**host tools: {}**\n\n",
-        target.name,
-        match target.metadata.tier {
-            Some(1) => "1",
-            Some(2) => "2",
-            Some(3) => "3",
-            _ => "UNKNOWN",
-        },
+    let render_cfg = |key| match target.cfg_value(key) {
+        Some("") | None => "none",
+        Some(value) => value,
+    };
+
+    let tier = match target.metadata.tier {
+        Some(tier @ 1..=3) => {
+            let url = match (&options.tier_policy_url, &options.base_url) {
+                (Some(url), _) => url.clone(),
+                (None, Some(base_url)) => page_url(base_url, "target-tier-policy.md"),
+                (None, None) => TIER_POLICY_URL.to_owned(),
+            };
+            format!("[Tier {tier}]({url}#tier-{tier}-target-policy)")
+        }
+        tier => format!("Tier: {}", render_tier(tier)),
+    };
+
+    let mut doc = canonical_url(&target.name, options)
+        .map(|url| format!("<!-- canonical: {url} -->\n"))
+        .unwrap_or_default();
+    doc.push_str(&format!("# {}\n\n", target.name));
+    // The one-line summary from the target metadata of rustc.
+    if let Some(description) = &target.metadata.description {
+        doc.push_str(&format!("*{}*\n\n", description.trim()));
+    }
+    if !target.aliases.is_empty() {
+        let aliases = target.aliases.iter().map(|alias| format!("`{alias}`")).collect::<Vec<_>>();
+        doc.push_str(&format!("**Also known as:** {}\n\n", aliases.join(", ")));
+    }
+    doc.push_str(&format!(
+        "**{tier}**\n\n**std: {}**\n\n**host tools: {}**\n\n",
         render_header_option_bool(target.metadata.std),
         render_header_option_bool(target.metadata.host_tools),
-    );
+    ));
+    doc.push_str(&format!(
+        "**vendor: {}**\n\n**env: {}**\n\n",
+        render_cfg("target_vendor"),
+        render_cfg("target_env"),
+    ));
+
+    if target.rustc_unavailable {
+        doc.push_str("*Information from rustc is unavailable for this target.*\n\n");
+    }
+
+    if let Some(version) = &target.stabilized_in {
+        doc.push_str(&format!("**Available since Rust {version}**\n\n"));
+    }
+
+    if let Some(status) = target.maintenance_status {
+        doc.push_str(&format!("**Maintenance: {}**\n\n", status.label()));
+    }
+
+    if let Some(ci) = &target.ci {
+        doc.push_str(&format!("**CI: [{}]({})**\n\n", ci.label, ci.url));
+    }
+
+    if let Some(nightly_tier) =
+        target.nightly_tier.filter(|tier| target.metadata.tier != Some(*tier))
+    {
+        let change = match target.metadata.tier {
+            Some(tier) if tier < nightly_tier => "demoted",
+            _ => "promoted",
+        };
+        doc.push_str(&render_alert(
+            AlertKind::Note,
+            &format!(
+                "This target is tier {nightly_tier} on nightly. \
+                It was {change} after the current stable release, which has the tier above."
+            ),
+        ));
+    }
+
+    if target.experimental {
+        doc.push_str(&render_alert(
+            AlertKind::Warning,
+            "This target is experimental, even within its tier.",
+        ));
+    }
+
+    if target.metadata.std == Some(false) {
+        doc.push_str(&render_alert(
+            AlertKind::Note,
+            "This target does not support `std`, only `core` and possibly `alloc`.",
+        ));
+    }
+
+    let unusual = UNUSUAL_CFGS
+        .iter()
+        .filter(|(key, value, _)| target.cfg_value(key) == Some(value))
+        .map(|(_, _, note)| *note)
+        .collect::<Vec<_>>();
+    if !unusual.is_empty() {
+        let note = format!(
+            "{}\nCode written for other targets may wrongly assume otherwise.",
+            unusual.join("\n")
+        );
+        doc.push_str(&render_alert(AlertKind::Note, &note));
+    }
 
+    // The sections are collected first, they are listed in the table of contents above them.
+    let mut sections = String::new();
+    let mut contents = Vec::new();
     let mut section = |name: &str, content: &str| {
-        doc.push_str("## ");
-        doc.push_str(name.trim());
-        doc.push('\n');
-        doc.push_str(content.trim());
-        doc.push_str("\n\n");
+        contents.push(format!("- [{}](#{})", name.trim(), section_anchor(name)));
+        sections.push_str("## ");
+        sections.push_str(name.trim());
+        sections.push('\n');
+        sections.push_str(content.trim());
+        sections.push_str("\n\n");
     };
 
-    let maintainers_content = if target.maintainers.is_empty() {
-        "This target does not have any maintainers!".to_owned()
+    section("Maintainers", &render_maintainers_content(target, options));
+
+    if !target.min_versions.is_empty() && !options.checklist_requirements {
+        let rows = target
+            .min_versions
+            .iter()
+            .map(|(name, version)| format!("| {name} | {version} |"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        section(
+            "Minimum versions",
+            &format!("| Component | Minimum version |\n|-----------|-----------------|\n{rows}"),
+        );
+    }
+
+    for (section_name, _) in crate::SECTIONS {
+        if let Some(content) = render_section_content(target, section_name, options) {
+            section(section_name, &content);
+        }
+    }
+
+    if let Some(abi_content) = render_abi_content(target) {
+        section("ABI", &abi_content);
+    }
+
+    if let Some(notes) = &target.binary_size_notes {
+        section("Binary size", notes);
+    }
+
+    if !target.proposals.is_empty() {
+        let links = target
+            .proposals
+            .iter()
+            .map(|proposal| format!("- [{}]({})", proposal.title, proposal.url))
+            .collect::<Vec<_>>()
+            .join("\n");
+        section("Related proposals", &links);
+    }
+
+    if !target.see_also.is_empty() {
+        let links = target
+            .see_also
+            .iter()
+            .map(|related| {
+                let url = match &options.base_url {
+                    Some(_) => canonical_url(related, options).unwrap_or_default(),
+                    None => format!("../{}", target_page_path(related, options)),
+                };
+                format!("- [{related}]({url})")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        section("See also", &links);
+    }
+
+    section("cfg", &render_cfg_content(target, options));
+
+    doc.push_str(&contents.join("\n"));
+    doc.push_str("\n\n");
+    doc.push_str(&sections);
+    doc
+}
+
+/// The anchor of a heading, like GitHub and mdBook generate it: lowercase, with hyphens
+/// instead of spaces and without other punctuation.
+fn section_anchor(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|char| match char {
+            ' ' => Some('-'),
+            char if char.is_alphanumeric() || char == '-' || char == '_' => Some(char),
+            _ => None,
+        })
+        .collect()
+}
+
+/// cfg values that most targets don't have, with the note rendered for targets that do.
+const UNUSUAL_CFGS: &[(&str, &str, &str)] = &[
+    ("target_endian", "big", "This target is big-endian."),
+    ("target_pointer_width", "16", "This target has 16-bit pointers."),
+];
+
+fn render_maintainers_content(target: &TargetInfo, options: &RenderOptions) -> String {
+    if target.maintainers.is_empty() {
+        let message = options
+            .no_maintainers_message
+            .as_deref()
+            .unwrap_or("This target does not have any maintainers!");
+        if options.no_maintainers_alert {
+            render_alert(AlertKind::Warning, message)
+        } else {
+            message.to_owned()
+        }
     } else {
         format!(
             "This target is maintained by:\n{}",
@@ -41,46 +293,410 @@ pub fn render_target_md(target: &TargetInfo) -> String {
                 .maintainers
                 .iter()
                 .map(|maintainer| {
-                    let maintainer = if maintainer.starts_with('@') && !maintainer.contains(' ') {
-                        format!(
-                            "[@{0}](https://github.com/{0})",
-                            maintainer.strip_prefix("@").unwrap()
-                        )
+                    let avatar = if options.maintainer_avatars {
+                        render_avatar(maintainer).unwrap_or_default()
                     } else {
-                        maintainer.to_owned()
+                        String::new()
                     };
-
-                    format!("- {maintainer}")
+                    format!("- {avatar}{}", render_maintainer(maintainer))
                 })
                 .collect::<Vec<_>>()
                 .join("\n")
         )
+    }
+}
+
+/// Renders the content of a section, stubbing it out if the target doesn't have it
+/// or it's a draft. Optional sections are left out instead of stubbed out.
+fn render_section_content(
+    target: &TargetInfo,
+    section_name: &str,
+    options: &RenderOptions,
+) -> Option<String> {
+    let value = target
+        .sections
+        .iter()
+        .find(|(name, _)| name == section_name)
+        .filter(|(name, _)| options.include_drafts || !target.draft_sections.contains(name));
+
+    let mut section_content = match value {
+        Some((_, value)) => value.clone(),
+        None => STUB.to_owned(),
     };
 
-    section("Maintainers", &maintainers_content);
+    let is_requirements = section_name == "Requirements";
+    if is_requirements && options.checklist_requirements {
+        section_content = render_requirements_checklist(target, value.map(|(_, v)| v.as_str()));
+    } else if is_requirements && !target.toolchain_requirements.is_empty() {
+        let rows = target
+            .toolchain_requirements
+            .iter()
+            .map(|requirement| format!("| {} | {} |", requirement.tool, requirement.min_version))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let table = format!("| Tool | Minimum version |\n|------|-----------------|\n{rows}");
+        section_content = match value {
+            Some(_) => format!("{table}\n\n{section_content}"),
+            None => table,
+        };
+    }
 
-    for section_name in crate::SECTIONS {
-        let value = target.sections.iter().find(|(name, _)| name == section_name);
+    if section_name == "Testing" && !target.tested_by.is_empty() {
+        let rows = target
+            .tested_by
+            .iter()
+            .map(|tested_by| {
+                format!("| {} | [{}]({}) |", tested_by.platform, tested_by.runner, tested_by.url)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let table = format!("| Platform | CI runner |\n|----------|-----------|\n{rows}");
+        section_content = match value {
+            Some(_) => format!("{section_content}\n\n{table}"),
+            None => table,
+        };
+    }
 
-        let section_content = match value {
-            Some((_, value)) => value.clone(),
-            None => "Unknown.".to_owned(),
+    if let Some(example) =
+        target.build_example.as_ref().filter(|_| section_name == "Building the target")
+    {
+        let block = format!("```bash\n{}\n```", example.trim());
+        section_content = match value {
+            Some(_) => format!("{section_content}\n\n{block}"),
+            None => block,
         };
-        section(section_name, &section_content);
     }
 
-    let cfg_text = target
-        .target_cfgs
+    let section_maintainers =
+        target.section_maintainers.iter().find(|(name, _)| name == section_name);
+    if let Some((_, maintainers)) = section_maintainers.filter(|_| value.is_some()) {
+        let maintainers =
+            maintainers.iter().map(|m| render_maintainer(m)).collect::<Vec<_>>().join(", ");
+        section_content.push_str(&format!("\n\n*This section is maintained by {maintainers}.*"));
+    }
+
+    // Unlike the sections of the patterns, shared notes are added to the section.
+    let shared = target.shared_notes.iter().filter_map(|notes| {
+        let (_, content) = notes.sections.iter().find(|(name, _)| name == section_name)?;
+        Some(format!("*Shared by all `{}` targets:*\n\n{content}", notes.family))
+    });
+    for note in shared {
+        section_content = match section_content.as_str() {
+            STUB => note,
+            _ => format!("{section_content}\n\n{note}"),
+        };
+    }
+
+    let optional = crate::section_kind(section_name) == Some(crate::SectionKind::Optional);
+    (section_content != STUB || !optional).then_some(section_content)
+}
+
+/// Renders the minimum versions, toolchain requirements and the list items of the
+/// Requirements section as one checklist. Other text of the section stays above the checklist.
+fn render_requirements_checklist(target: &TargetInfo, requirements: Option<&str>) -> String {
+    let mut items = target
+        .min_versions
         .iter()
-        .map(|(key, value)| format!("- `{key}` = `{value}`"))
-        .collect::<Vec<_>>()
-        .join("\n");
-    let cfg_content =
-        format!("This target defines the following target-specific cfg values:\n{cfg_text}\n");
+        .map(|(name, version)| format!("{name} {version} or newer"))
+        .chain(target.toolchain_requirements.iter().map(|requirement| {
+            format!("{} {} or newer", requirement.tool, requirement.min_version)
+        }))
+        .collect::<Vec<_>>();
 
-    section("cfg", &cfg_content);
+    let mut text = Vec::new();
+    let mut in_codeblock = false;
+    for line in requirements.unwrap_or_default().lines() {
+        in_codeblock ^= line.starts_with("```");
+        let item = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "));
+        match item.filter(|_| !in_codeblock) {
+            Some(item) => items.push(item.trim().to_owned()),
+            None => text.push(line),
+        }
+    }
 
-    doc
+    let text = text.join("\n");
+    let checklist = items.iter().map(|item| format!("- [ ] {item}")).collect::<Vec<_>>().join("\n");
+    match (text.trim(), checklist.is_empty()) {
+        ("", true) => STUB.to_owned(),
+        ("", false) => checklist,
+        (text, true) => text.to_owned(),
+        (text, false) => format!("{text}\n\n{checklist}"),
+    }
+}
+
+/// Renders the ABI from the `target_abi` cfg and the `abi_notes`, if there is anything to say.
+fn render_abi_content(target: &TargetInfo) -> Option<String> {
+    let abi = target.cfg_value("target_abi").filter(|abi| !abi.is_empty());
+    let abi = abi.map(|abi| format!("This target uses the `{abi}` ABI."));
+
+    match (abi, &target.abi_notes) {
+        (None, None) => None,
+        (Some(abi), None) => Some(abi),
+        (None, Some(notes)) => Some(notes.trim().to_owned()),
+        (Some(abi), Some(notes)) => Some(format!("{abi}\n\n{}", notes.trim())),
+    }
+}
+
+fn render_cfg_content(target: &TargetInfo, options: &RenderOptions) -> String {
+    if target.rustc_unavailable {
+        return "The cfg values of this target are unavailable, rustc failed for it.\n".to_owned();
+    }
+    let render_list = |cfgs: &[&(String, String)]| {
+        cfgs.iter()
+            .map(|(key, value)| format!("- {} = {}", render_code(key), render_code(value)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let mut cfg_text = if options.flat_cfg {
+        render_list(&target.target_cfgs.iter().collect::<Vec<_>>())
+    } else {
+        let group_of = |key: &str| {
+            CFG_GROUPS.iter().position(|(_, keys)| keys.contains(&key)).unwrap_or(CFG_GROUPS.len())
+        };
+        // The cfgs without a group go into the last one.
+        let names = CFG_GROUPS.iter().map(|(name, _)| *name).chain(["Other"]);
+        names
+            .enumerate()
+            .filter_map(|(group, name)| {
+                let cfgs = target
+                    .target_cfgs
+                    .iter()
+                    .filter(|(key, _)| group_of(key) == group)
+                    .collect::<Vec<_>>();
+                (!cfgs.is_empty()).then(|| format!("### {name}\n\n{}", render_list(&cfgs)))
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+    let threshold = options.cfg_collapse_threshold.unwrap_or(CFG_COLLAPSE_THRESHOLD);
+    if target.target_cfgs.len() > threshold {
+        // The blank lines make the list inside the HTML block render as markdown.
+        cfg_text =
+            format!("<details><summary>Target cfg values</summary>\n\n{cfg_text}\n\n</details>");
+    }
+    // The headings of the groups need a blank line before them.
+    let separator = if options.flat_cfg { "\n" } else { "\n\n" };
+    format!("This target defines the following target-specific cfg values:{separator}{cfg_text}\n")
+}
+
+/// The groups of the cfg values on the target pages, with the keys in them.
+const CFG_GROUPS: &[(&str, &[&str])] = &[
+    ("Architecture", &["target_arch"]),
+    ("OS/env", &["target_os", "target_env", "target_vendor"]),
+    ("ABI", &["target_abi"]),
+    ("Data model", &["target_pointer_width", "target_endian"]),
+];
+
+/// Renders text as inline code. Backslashes don't escape backticks in code, so the text is
+/// wrapped in more backticks than it contains in a row, and padded if it starts or ends with one.
+fn render_code(text: &str) -> String {
+    let longest_run = text.split(|char| char != '`').map(str::len).max().unwrap_or_default();
+    let fence = "`".repeat(longest_run + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{fence} {text} {fence}")
+    } else {
+        format!("{fence}{text}{fence}")
+    }
+}
+
+/// Renders a target page from a template instead of the built-in layout.
+/// The template contains placeholders like `{{tier}}` or `{{section:Testing}}` that get
+/// replaced with the rendered information.
+pub fn render_target_md_with_template(
+    target: &TargetInfo,
+    template: &str,
+    options: &RenderOptions,
+) -> Result<String> {
+    let mut doc = String::new();
+    let mut rest = template;
+
+    while let Some((before, after)) = rest.split_once("{{") {
+        let (placeholder, after) =
+            after.split_once("}}").ok_or_eyre("unclosed `{{` in page template")?;
+        doc.push_str(before);
+
+        let value = match placeholder.trim() {
+            "name" => target.name.clone(),
+            "tier" => render_tier(target.metadata.tier).to_owned(),
+            "description" => target.metadata.description.clone().unwrap_or_default(),
+            "maintainers" => render_maintainers_content(target, options),
+            "cfg" => render_cfg_content(target, options),
+            "canonical_url" => canonical_url(&target.name, options).unwrap_or_default(),
+            placeholder => match placeholder.strip_prefix("section:") {
+                Some(name) if crate::section_kind(name).is_some() => {
+                    render_section_content(target, name, options).unwrap_or_default()
+                }
+                Some(name) => bail!(
+                    "`{name}` in page template is not a section, must be one of {:?}",
+                    crate::section_names()
+                ),
+                None => bail!("unknown placeholder `{{{{{placeholder}}}}}` in page template"),
+            },
+        };
+        doc.push_str(&value);
+        rest = after;
+    }
+    doc.push_str(rest);
+
+    Ok(doc)
+}
+
+/// The kinds of GitHub-flavored alerts supported by mdBook and GitHub.
+#[derive(Clone, Copy)]
+pub enum AlertKind {
+    Note,
+    Warning,
+}
+
+/// Renders an alert block like `> [!WARNING]`, followed by a blank line.
+pub fn render_alert(kind: AlertKind, text: &str) -> String {
+    let kind = match kind {
+        AlertKind::Note => "NOTE",
+        AlertKind::Warning => "WARNING",
+    };
+    let mut alert = format!("> [!{kind}]\n");
+    for line in text.trim().lines() {
+        alert.push_str(format!("> {line}").trim_end());
+        alert.push('\n');
+    }
+    alert.push('\n');
+    alert
+}
+
+fn render_tier(tier: Option<u8>) -> &'static str {
+    match tier {
+        Some(1) => "1",
+        Some(2) => "2",
+        Some(3) => "3",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Renders a maintainer, linking GitHub handles to their profile.
+fn render_maintainer(maintainer: &str) -> String {
+    match maintainer.strip_prefix('@').filter(|handle| !handle.contains(' ')) {
+        Some(handle) => match handle.split_once('/') {
+            Some((org, team)) => {
+                format!("[@{handle}](https://github.com/orgs/{org}/teams/{team})")
+            }
+            None => format!("[@{handle}](https://github.com/{handle})"),
+        },
+        None => maintainer.to_owned(),
+    }
+}
+
+/// Renders the GitHub avatar of a maintainer given as a `@handle`, followed by a space.
+/// Teams and free-form names don't have one.
+fn render_avatar(maintainer: &str) -> Option<String> {
+    let handle = maintainer.strip_prefix('@').filter(|handle| !handle.contains([' ', '/']))?;
+    Some(format!(
+        "<img src=\"https://github.com/{handle}.png?size=40\" alt=\"\" width=\"20\" height=\"20\"> "
+    ))
+}
+
+/// Renders a Graphviz DOT graph linking every pattern to the targets it matches.
+/// Pattern nodes are labeled with what they contribute, edges with target-specific footnotes.
+pub fn render_graph(patterns: &[ParsedTargetInfoFile], targets: &[TargetInfo]) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut dot = "digraph target_infos {\n    rankdir=LR;\n".to_owned();
+    let mut edges = Vec::new();
+    let mut matched_targets = Vec::new();
+
+    for pattern in patterns {
+        let mut label = vec![pattern.pattern.clone()];
+        if !pattern.maintainers.is_empty() {
+            label.push(format!("maintainers: {}", pattern.maintainers.join(", ")));
+        }
+        if !pattern.sections.is_empty() {
+            let names = pattern.sections.iter().map(|(name, _)| name.as_str());
+            label.push(format!("sections: {}", names.collect::<Vec<_>>().join(", ")));
+        }
+
+        let node = quote(&format!("pattern:{}", pattern.pattern));
+        // `\n` is a line break inside of DOT labels.
+        let label = quote(&label.join("\n")).replace('\n', "\\n");
+        dot.push_str(&format!("    {node} [shape=box, label={label}];\n"));
+
+        for target in targets {
+            if !glob_match::glob_match(&pattern.pattern, &target.name) {
+                continue;
+            }
+            if !matched_targets.contains(&&target.name) {
+                matched_targets.push(&target.name);
+            }
+
+            let target_node = quote(&format!("target:{}", target.name));
+            match pattern.footnotes.get(&target.name) {
+                Some(footnotes) => edges.push(format!(
+                    "    {node} -> {target_node} [label={}];\n",
+                    quote(&format!(
+                        "footnotes: {}",
+                        footnotes.iter().map(Footnote::name).collect::<Vec<_>>().join(", ")
+                    ))
+                )),
+                None => edges.push(format!("    {node} -> {target_node};\n")),
+            }
+        }
+    }
+
+    for target in matched_targets {
+        dot.push_str(&format!(
+            "    {} [label={}];\n",
+            quote(&format!("target:{target}")),
+            quote(target)
+        ));
+    }
+    for edge in edges {
+        dot.push_str(&edge);
+    }
+    dot.push_str("}\n");
+
+    dot
+}
+
+/// Renders a CSV file with a `target,tier,maintainer` row for every maintainer of every target.
+/// The tier is empty if it is unknown.
+pub fn render_maintainers_csv(targets: &[TargetInfo]) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_owned()
+        }
+    };
+
+    let mut csv = "target,tier,maintainer\n".to_owned();
+    for target in targets {
+        let tier = target.metadata.tier.map(|tier| tier.to_string()).unwrap_or_default();
+        for maintainer in &target.maintainers {
+            csv.push_str(&format!("{},{tier},{}\n", field(&target.name), field(maintainer)));
+        }
+    }
+    csv
+}
+
+/// Renders a markdown table of the patterns matching every target, in resolution order,
+/// together with the resolved tier and number of maintainers.
+pub fn render_resolution(patterns: &[ParsedTargetInfoFile], targets: &[TargetInfo]) -> String {
+    let mut table = "| Target | Patterns | Tier | Maintainers |\n\
+        |--------|----------|------|-------------|\n"
+        .to_owned();
+    for target in targets {
+        let matching = patterns
+            .iter()
+            .filter(|pattern| glob_match::glob_match(&pattern.pattern, &target.name))
+            .map(|pattern| format!("`{}`", pattern.pattern))
+            .collect::<Vec<_>>();
+        let matching = if matching.is_empty() { "none".to_owned() } else { matching.join(", ") };
+        table.push_str(&format!(
+            "| {} | {matching} | {} | {} |\n",
+            target.name,
+            render_tier(target.metadata.tier),
+            target.maintainers.len(),
+        ));
+    }
+    table
 }
 
 /// Replaces inner part of the form
@@ -102,76 +718,314 @@ fn replace_section(prev_content: &str, section_name: &str, replacement: &str) ->
     Ok(new)
 }
 
+/// The files with sections that [`render_static`] replaces, relative to the `src` directory.
+/// Replacing the sections removes their markers, so the files can only be filled in once.
+pub const STATIC_FILES: [&str; 3] =
+    ["platform-support/targets.md", "platform-support.md", "SUMMARY.md"];
+
+/// The page listing the targets seeking maintainers, relative to the `src` directory.
+const SEEKING_MAINTAINERS_PAGE: &str = "platform-support/seeking-maintainers.md";
+
 /// Renders the non-target files like `SUMMARY.md` that depend on the target.
-pub fn render_static(check_only: bool, src_output: &Path, targets: &[TargetInfo]) -> Result<()> {
+pub fn render_static(
+    writer: &mut OutputWriter,
+    src_output: &Path,
+    targets: &[TargetInfo],
+    options: &RenderOptions,
+) -> Result<()> {
     let targets_file = src_output.join("platform-support").join("targets.md");
     let old_targets = fs::read_to_string(&targets_file).wrap_err("reading summary file")?;
 
-    let target_list = targets
-        .iter()
-        .map(|target| format!("- [{0}](platform-support/targets/{0}.md)", target.name))
-        .collect::<Vec<_>>()
-        .join("\n");
+    // `targets.md` lives in `platform-support`, `SUMMARY.md` in `src`.
+    let target_list = render_target_list(targets, |target| target_page_path(target, options));
 
     let new_targets =
         replace_section(&old_targets, "TARGET", &target_list).wrap_err("replacing targets.md")?;
 
-    if !check_only {
-        fs::write(targets_file, new_targets).wrap_err("writing targets.md")?;
-    }
+    writer.write(&targets_file, &new_targets).wrap_err("writing targets.md")?;
 
     let platform_support_main = src_output.join("platform-support.md");
     let platform_support_main_old =
         fs::read_to_string(&platform_support_main).wrap_err("reading platform-support.md")?;
     let platform_support_main_new =
-        render_platform_support_tables(&platform_support_main_old, targets)?;
+        render_platform_support_tables(&platform_support_main_old, targets, options)?;
 
-    if !check_only {
-        fs::write(platform_support_main, platform_support_main_new)
-            .wrap_err("writing platform-support.md")?;
-    }
+    writer
+        .write(&platform_support_main, &platform_support_main_new)
+        .wrap_err("writing platform-support.md")?;
 
     let summary = src_output.join("SUMMARY.md");
     let summary_old = fs::read_to_string(&summary).wrap_err("reading SUMMARY.md")?;
-    // indent the list
-    let summary_new =
-        replace_section(&summary_old, "TARGET_LIST", &target_list.replace("- ", "      - "))
-            .wrap_err("replacig SUMMARY.md")?;
-    if !check_only {
-        fs::write(summary, summary_new).wrap_err("writing SUMAMRY.md")?;
+    let arch_pages = if options.arch_pages { render_arch_pages(targets, options) } else { vec![] };
+    // The other generated pages, which mdBook also only renders when they are referenced.
+    // The arch pages are nested under the target list, with the targets.
+    let mut pages = arch_pages
+        .iter()
+        .map(|(arch, _)| ("      ", arch.clone(), format!("platform-support/arch/{arch}.md")))
+        .collect::<Vec<_>>();
+    pages.push((
+        "    ",
+        "Targets seeking maintainers".to_owned(),
+        SEEKING_MAINTAINERS_PAGE.to_owned(),
+    ));
+    // Without the markers, the entries are written by hand and only checked.
+    let summary_new = if summary_old.contains("<!-- TARGET_LIST SECTION START -->") {
+        let target_list =
+            render_target_list(targets, |target| target_page_src_path(target, options));
+        // indent the list
+        let mut entries = target_list.replace("- ", "      - ");
+        for (indent, title, path) in &pages {
+            entries.push_str(&format!("\n{indent}- [{title}]({path})"));
+        }
+        replace_section(&summary_old, "TARGET_LIST", &entries).wrap_err("replacig SUMMARY.md")?
+    } else {
+        let missing = missing_summary_entries(&summary_old, targets, options);
+        if !missing.is_empty() {
+            bail!("SUMMARY.md does not reference the target pages of {}", missing.join(", "));
+        }
+        if let Some((_, _, path)) =
+            pages.iter().find(|(_, _, path)| !summary_old.contains(&format!("]({path})")))
+        {
+            bail!("SUMMARY.md does not reference {path}");
+        }
+        summary_old
+    };
+    writer.write(&summary, &summary_new).wrap_err("writing SUMAMRY.md")?;
+
+    let seeking_maintainers = src_output.join(SEEKING_MAINTAINERS_PAGE);
+    writer
+        .write(&seeking_maintainers, &render_seeking_maintainers_page(targets, options))
+        .wrap_err("writing seeking-maintainers.md")?;
+
+    if options.arch_pages {
+        let arch_dir = src_output.join("platform-support").join("arch");
+        writer.create_dir_all(&arch_dir).wrap_err("creating platform-support/arch dir")?;
+        for (arch, page) in arch_pages {
+            writer
+                .write(&arch_dir.join(format!("{arch}.md")), &page)
+                .wrap_err("writing arch page")?;
+        }
+    }
+
+    if options.json {
+        writer
+            .write(&src_output.join("targets.json"), &render_json(targets, options))
+            .wrap_err("writing targets.json")?;
     }
 
     Ok(())
 }
 
+/// A target in `targets.json`.
+#[derive(Serialize)]
+struct TargetJson<'a> {
+    name: &'a str,
+    tier: Option<u8>,
+    maintainers: &'a [String],
+    /// The sections provided by the target info files, without stubs.
+    sections: BTreeMap<&'a str, &'a str>,
+    footnotes: &'a [Footnote],
+    /// The `target_` cfg values, without the quotes rustc prints.
+    cfg: Vec<(&'a str, &'a str)>,
+    description: Option<&'a str>,
+    std: Option<bool>,
+    host_tools: Option<bool>,
+}
+
+/// Renders the information about all targets as JSON, for tools that shouldn't parse the pages.
+pub fn render_json(targets: &[TargetInfo], options: &RenderOptions) -> String {
+    let targets = targets
+        .iter()
+        .map(|target| TargetJson {
+            name: &target.name,
+            tier: target.metadata.tier,
+            maintainers: &target.maintainers,
+            sections: target
+                .sections
+                .iter()
+                .filter(|(name, _)| options.include_drafts || !target.draft_sections.contains(name))
+                .map(|(name, content)| (name.as_str(), content.as_str()))
+                .collect(),
+            footnotes: &target.footnotes,
+            cfg: target
+                .target_cfgs
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.trim_matches('"')))
+                .collect(),
+            description: target.metadata.description.as_deref(),
+            std: target.metadata.std,
+            host_tools: target.metadata.host_tools,
+        })
+        .collect::<Vec<_>>();
+    let mut json = serde_json::to_string_pretty(&targets).expect("serializing targets.json");
+    json.push('\n');
+    json
+}
+
+/// Renders the page listing the targets with the `seeking-maintainers` maintenance status,
+/// which lives next to `targets.md`.
+fn render_seeking_maintainers_page(targets: &[TargetInfo], options: &RenderOptions) -> String {
+    let seeking = targets
+        .iter()
+        .filter(|target| target.maintenance_status == Some(MaintenanceStatus::SeekingMaintainers))
+        .map(|target| {
+            format!(
+                "| [`{}`]({}) | {} |\n",
+                target.name,
+                target_page_path(&target.name, options),
+                render_tier(target.metadata.tier)
+            )
+        })
+        .collect::<String>();
+
+    let mut page = "# Targets seeking maintainers\n\n".to_owned();
+    if seeking.is_empty() {
+        page.push_str("No targets are currently seeking maintainers.\n");
+    } else {
+        page.push_str(
+            "These targets are looking for new maintainers. \
+            If you are interested in maintaining one of them, open an issue or a pull request.\n\n\
+            | Target | Tier |\n|--------|------|\n",
+        );
+        page.push_str(&seeking);
+    }
+    page
+}
+
+/// Renders a landing page for every `target_arch`, listing the targets of that architecture.
+/// Returns the architecture and the content of its page.
+fn render_arch_pages(targets: &[TargetInfo], options: &RenderOptions) -> Vec<(String, String)> {
+    let mut by_arch = BTreeMap::<&str, Vec<&TargetInfo>>::new();
+    for target in targets {
+        if let Some(arch) = target.cfg_value("target_arch") {
+            by_arch.entry(arch).or_default().push(target);
+        }
+    }
+
+    by_arch
+        .into_iter()
+        .map(|(arch, targets)| {
+            let rows = targets
+                .iter()
+                .map(|target| {
+                    format!(
+                        "| [`{}`](../{}) | {} |",
+                        target.name,
+                        target_page_path(&target.name, options),
+                        render_tier(target.metadata.tier)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let page = format!(
+                "# {arch}\n\nTargets for the `{arch}` architecture.\n\n| Target | Tier |\n|--------|------|\n{rows}\n"
+            );
+            (arch.to_owned(), page)
+        })
+        .collect()
+}
+
+/// Path of the page of a target, relative to the `platform-support` directory.
+pub fn target_page_path(target: &str, options: &RenderOptions) -> String {
+    format!("targets/{target}.{}", options.page_extension())
+}
+
+/// Path of the page of a target, relative to the `src` directory.
+pub fn target_page_src_path(target: &str, options: &RenderOptions) -> String {
+    format!("platform-support/{}", target_page_path(target, options))
+}
+
+/// Renders a markdown list linking to all target pages.
+fn render_target_list(targets: &[TargetInfo], path: impl Fn(&str) -> String) -> String {
+    targets
+        .iter()
+        .map(|target| format!("- [{}]({})", target.name, path(&target.name)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the targets whose page is not referenced in `SUMMARY.md`.
+/// mdBook only renders pages that are referenced there.
+fn missing_summary_entries<'a>(
+    summary: &str,
+    targets: &'a [TargetInfo],
+    options: &RenderOptions,
+) -> Vec<&'a str> {
+    targets
+        .iter()
+        .filter(|target| {
+            !summary.contains(&format!("]({})", target_page_src_path(&target.name, options)))
+        })
+        .map(|target| target.name.as_str())
+        .collect()
+}
+
 impl TargetInfo {
     fn has_host_tools(&self) -> bool {
         self.metadata.host_tools.unwrap_or(false)
     }
+
+    /// The value of the `key` cfg, without the quotes rustc prints.
+    fn cfg_value(&self, key: &str) -> Option<&str> {
+        let (_, value) = self.target_cfgs.iter().find(|(cfg, _)| cfg == key)?;
+        Some(value.trim_matches('"'))
+    }
 }
 
-fn render_platform_support_tables(content: &str, targets: &[TargetInfo]) -> Result<String> {
-    let replace_table = |content, name, tier_table| -> Result<String> {
-        let section_string = render_table(targets, tier_table)?;
-        replace_section(content, name, &section_string).wrap_err("replacing platform support.md")
+fn render_platform_support_tables(
+    content: &str,
+    targets: &[TargetInfo],
+    options: &RenderOptions,
+) -> Result<String> {
+    let replace_table = |content: &str, name: &str, tier_table: TierTable| -> Result<String> {
+        let marker = format!("<!-- {name} SECTION START -->");
+        // The header is written by hand, so it may be missing the newer columns.
+        if let Some(header) = table_header_before(content, &marker).and_then(|h| h.lines().next()) {
+            let header_columns = header.trim().trim_matches('|').split('|').count();
+            let columns = 2 + tier_table.columns.len();
+            if header_columns != columns {
+                let names = tier_table.columns.iter().map(|column| column.name);
+                let names =
+                    ["target"].into_iter().chain(names).chain(["notes"]).collect::<Vec<_>>();
+                bail!(
+                    "the table before {marker} in platform-support.md has {header_columns} columns, \
+                    but needs {columns}: {}",
+                    names.join(" | ")
+                );
+            }
+        }
+        let rows = targets.iter().filter(|target| (tier_table.filter)(target)).count();
+        let split = options
+            .split_tables_over
+            .filter(|max_rows| rows > *max_rows)
+            .and_then(|_| table_header_before(content, &marker))
+            .map(|header| TableSplit { header, id: name.to_lowercase() });
+        // The split tables repeat the header, so it's moved into them.
+        let content = match &split {
+            Some(split) => content.replacen(&format!("{}{marker}", split.header), &marker, 1),
+            None => content.to_owned(),
+        };
+
+        let mut section_string = render_table(targets, tier_table, split, options)?;
+        if options.partial {
+            let note = render_alert(AlertKind::Note, "This table only lists some of the targets.");
+            section_string.push_str("\n\n");
+            section_string.push_str(note.trim_end());
+        }
+        replace_section(&content, name, &section_string).wrap_err("replacing platform support.md")
     };
 
     let content = replace_table(
         content,
         "TIER1HOST",
-        TierTable {
-            filter: |target| target.metadata.tier == Some(1),
-            include_host: false,
-            include_std: false,
-        },
+        TierTable { filter: |target| target.metadata.tier == Some(1), columns: vec![] },
     )?;
     let content = replace_table(
         &content,
         "TIER2HOST",
         TierTable {
             filter: |target| target.metadata.tier == Some(2) && target.has_host_tools(),
-            include_host: false,
-            include_std: false,
+            columns: vec![],
         },
     )?;
     let content = replace_table(
@@ -179,8 +1033,7 @@ fn render_platform_support_tables(content: &str, targets: &[TargetInfo]) -> Resu
         "TIER2",
         TierTable {
             filter: |target| target.metadata.tier == Some(2) && !target.has_host_tools(),
-            include_host: false,
-            include_std: true,
+            columns: vec![STD_COLUMN],
         },
     )?;
     let content = replace_table(
@@ -188,11 +1041,26 @@ fn render_platform_support_tables(content: &str, targets: &[TargetInfo]) -> Resu
         "TIER3",
         TierTable {
             filter: |target| target.metadata.tier == Some(3),
-            include_host: true,
-            include_std: true,
+            columns: vec![STD_COLUMN, HOST_COLUMN, MAINTAINERS_COLUMN],
         },
     )?;
 
+    // With only some of the targets, the targets referencing a footnote may be missing.
+    if !options.partial {
+        let references = crate::check::footnote_references(&content);
+        let unreferenced = crate::check::footnote_definitions(&content)
+            .into_iter()
+            .filter(|name| !references.contains(name))
+            .map(|name| format!("`[^{name}]`"))
+            .collect::<Vec<_>>();
+        if !unreferenced.is_empty() {
+            bail!(
+                "platform-support.md defines footnotes that are never referenced: {}",
+                unreferenced.join(", ")
+            );
+        }
+    }
+
     Ok(content)
 }
 
@@ -204,54 +1072,174 @@ fn render_table_option_bool(bool: Option<bool>) -> &'static str {
     }
 }
 
+/// A column of a tier table, between the target and the notes.
+struct TableColumn {
+    name: &'static str,
+    value: fn(&TargetInfo, &RenderOptions) -> String,
+}
+
+const STD_COLUMN: TableColumn = TableColumn {
+    name: "std",
+    value: |target, _| render_family_outlier(target, "std", target.metadata.std),
+};
+
+const HOST_COLUMN: TableColumn = TableColumn {
+    name: "host",
+    value: |target, _| render_family_outlier(target, "host", target.metadata.host_tools),
+};
+
+/// The number of maintainers, linking to them on the target page.
+const MAINTAINERS_COLUMN: TableColumn = TableColumn {
+    name: "maintainers",
+    value: |target, options| {
+        let page = target_page_src_path(&target.name, options);
+        format!("[{}]({page}#maintainers)", target.maintainers.len())
+    },
+};
+
+/// Marks values that differ from most other targets of the family, they may be mistakes.
+fn render_family_outlier(target: &TargetInfo, field: &str, value: Option<bool>) -> String {
+    let value = render_table_option_bool(value);
+    if target.family_outliers.contains(&field) {
+        format!("{value} ⚠")
+    } else {
+        value.to_owned()
+    }
+}
+
 struct TierTable {
     filter: fn(&TargetInfo) -> bool,
-    include_std: bool,
-    include_host: bool,
+    /// The columns between the target and the notes, in order.
+    columns: Vec<TableColumn>,
 }
 
-fn render_table(targets: &[TargetInfo], table: TierTable) -> Result<String> {
+/// The header and delimiter row of the table right before `marker`, if there is one.
+fn table_header_before<'a>(content: &'a str, marker: &str) -> Option<&'a str> {
+    let before = &content[..content.find(marker)?];
+    let mut lines = before.strip_suffix('\n')?.rsplitn(3, '\n');
+    let delimiter = lines.next()?;
+    let header = lines.next()?;
+    let is_delimiter = delimiter.contains('-')
+        && delimiter.chars().all(|char| matches!(char, '-' | '|' | ':' | ' '));
+    if !is_delimiter || !header.contains('|') {
+        return None;
+    }
+    Some(&before[before.len() - header.len() - delimiter.len() - 2..])
+}
+
+/// Splitting a tier table into one table per `target_arch`.
+struct TableSplit<'a> {
+    /// The header and delimiter row repeated for every table.
+    header: &'a str,
+    /// Prefix of the ids of the subsections, unique across all tables of the page.
+    id: String,
+}
+
+/// Renders the rows of a tier table. When split, the rows are grouped into a table for every
+/// `target_arch`, in subsections linked to from a list.
+fn render_table(
+    targets: &[TargetInfo],
+    table: TierTable,
+    split: Option<TableSplit<'_>>,
+    options: &RenderOptions,
+) -> Result<String> {
     let mut rows = Vec::new();
+    let mut footnote_definitions = Vec::new();
 
-    let targets = targets.iter().filter(|target| (table.filter)(target));
+    let mut targets = targets.iter().filter(|target| (table.filter)(target)).collect::<Vec<_>>();
+    // Experimental targets go last, otherwise the order is preserved.
+    targets.sort_by_key(|target| target.experimental);
 
     for target in targets {
         let meta = &target.metadata;
 
         let mut notes = meta.description.as_deref().unwrap_or("unknown").to_owned();
 
+        if let Some(max_len) = options.max_notes_len {
+            if notes.chars().count() > max_len {
+                let truncated = notes.chars().take(max_len).collect::<String>();
+                notes = format!(
+                    "{}[…]({})",
+                    truncated.trim_end(),
+                    target_page_src_path(&target.name, options)
+                );
+            }
+        }
+
         if !target.footnotes.is_empty() {
             let footnotes_str = target
                 .footnotes
                 .iter()
-                .map(|footnote| format!("[^{}]", footnote))
+                .map(|footnote| match footnote {
+                    Footnote::Shared(name) => format!("[^{name}]"),
+                    Footnote::Scoped { name, content } => {
+                        // Scoped footnotes are prefixed with the target to avoid collisions
+                        // with the footnotes of other targets in the same table.
+                        let name = format!("{}-{name}", target.name);
+                        footnote_definitions.push(format!("[^{name}]: {}", content.trim()));
+                        format!("[^{name}]")
+                    }
+                })
                 .collect::<Vec<_>>()
                 .join(" ");
 
             notes = format!("{notes} {footnotes_str}");
         }
 
-        let std = if table.include_std {
-            let std = render_table_option_bool(meta.std);
-            format!(" | {std}")
-        } else {
-            String::new()
-        };
+        let mut columns = String::new();
+        for column in &table.columns {
+            let value = (column.value)(target, options);
+            if value.contains(['|', '\n']) {
+                bail!(
+                    "the `{}` column of target {} contains a `|` or newline: {value}",
+                    column.name,
+                    target.name
+                );
+            }
+            columns.push_str(" | ");
+            columns.push_str(&value);
+        }
 
-        let host = if table.include_host {
-            let host = render_table_option_bool(meta.host_tools);
-            format!(" | {host}")
-        } else {
-            String::new()
-        };
+        let experimental = if target.experimental { " *(experimental)*" } else { "" };
 
-        rows.push(format!(
-            "[`{0}`](platform-support/targets/{0}.md){std}{host} | {notes}",
-            target.name
-        ));
+        let row = format!(
+            "[`{}`]({}){experimental}{columns} | {notes}",
+            target.name,
+            target_page_src_path(&target.name, options)
+        );
+        rows.push((target.cfg_value("target_arch").unwrap_or("unknown"), row));
     }
 
-    let result = rows.join("\n");
+    let mut result = match split {
+        Some(TableSplit { header, id }) => {
+            let mut by_arch = BTreeMap::<&str, Vec<String>>::new();
+            for (arch, row) in rows {
+                by_arch.entry(arch).or_default().push(row);
+            }
+            let links = by_arch
+                .keys()
+                .map(|arch| format!("- [`{arch}`](#{id}-{arch})"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let tables = by_arch
+                .iter()
+                .map(|(arch, rows)| {
+                    format!("#### `{arch}` {{#{id}-{arch}}}\n\n{header}{}", rows.join("\n"))
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            format!("{links}\n\n{tables}")
+        }
+        None => rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>().join("\n"),
+    };
+
+    if !footnote_definitions.is_empty() {
+        result.push_str("\n\n");
+        result.push_str(&footnote_definitions.join("\n"));
+    }
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,1213 @@
+use super::RenderOptions;
+use crate::{
+    parse::{
+        Footnote, MaintenanceStatus, ParsedTargetInfoFile, Proposal, TestedBy, ToolchainRequirement,
+    },
+    RustcTargetMetadata, TargetInfo,
+};
+
+#[test]
+fn section_maintainer() {
+    let target = TargetInfo {
+        name: "cat-unknown-linux-gnu".to_owned(),
+        maintainers: vec!["@overall".to_owned()],
+        sections: vec![("Testing".to_owned(), "Pet the cat.".to_owned())],
+        section_maintainers: vec![("Testing".to_owned(), vec!["@tester".to_owned()])],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+
+    assert!(doc.contains(
+        "## Testing\nPet the cat.\n\n*This section is maintained by [@tester](https://github.com/tester).*\n\n"
+    ));
+    assert!(doc.contains("## Requirements\nUnknown.\n\n"));
+}
+
+#[test]
+fn graph() {
+    let linux = ParsedTargetInfoFile {
+        pattern: "*-linux-*".to_owned(),
+        maintainers: vec!["@penguin".to_owned()],
+        ..Default::default()
+    };
+    let x86_64 = ParsedTargetInfoFile {
+        pattern: "x86_64-*".to_owned(),
+        sections: vec![("Testing".to_owned(), "CI.".to_owned())],
+        footnotes: [(
+            "x86_64-unknown-linux-gnu".to_owned(),
+            vec![Footnote::Shared("glibc".to_owned())],
+        )]
+        .into(),
+        ..Default::default()
+    };
+    let targets =
+        ["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu", "x86_64-pc-windows-msvc"]
+            .map(|name| TargetInfo { name: name.to_owned(), ..Default::default() });
+
+    let dot = super::render_graph(&[linux, x86_64], &targets);
+
+    assert_eq!(
+        dot,
+        r#"digraph target_infos {
+    rankdir=LR;
+    "pattern:*-linux-*" [shape=box, label="*-linux-*\nmaintainers: @penguin"];
+    "pattern:x86_64-*" [shape=box, label="x86_64-*\nsections: Testing"];
+    "target:x86_64-unknown-linux-gnu" [label="x86_64-unknown-linux-gnu"];
+    "target:aarch64-unknown-linux-gnu" [label="aarch64-unknown-linux-gnu"];
+    "target:x86_64-pc-windows-msvc" [label="x86_64-pc-windows-msvc"];
+    "pattern:*-linux-*" -> "target:x86_64-unknown-linux-gnu";
+    "pattern:*-linux-*" -> "target:aarch64-unknown-linux-gnu";
+    "pattern:x86_64-*" -> "target:x86_64-unknown-linux-gnu" [label="footnotes: glibc"];
+    "pattern:x86_64-*" -> "target:x86_64-pc-windows-msvc";
+}
+"#
+    );
+}
+
+#[test]
+fn truncated_notes() {
+    let description = "ARM64 Linux with a very long description that goes on and on";
+    let targets = [TargetInfo {
+        name: "aarch64-unknown-linux-gnu".to_owned(),
+        footnotes: vec![Footnote::Shared("glibc".to_owned())],
+        metadata: RustcTargetMetadata {
+            description: Some(description.to_owned()),
+            tier: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    }];
+    let table =
+        super::TierTable { filter: |target| target.metadata.tier == Some(1), columns: vec![] };
+    let options = RenderOptions { max_notes_len: Some(10), ..Default::default() };
+
+    let rendered = super::render_table(&targets, table, None, &options).unwrap();
+    assert_eq!(
+        rendered,
+        "[`aarch64-unknown-linux-gnu`](platform-support/targets/aarch64-unknown-linux-gnu.md) | ARM64 Linu[…](platform-support/targets/aarch64-unknown-linux-gnu.md) [^glibc]"
+    );
+
+    let doc = super::render_target_md(&targets[0], &RenderOptions::default());
+    assert!(doc.contains(description));
+}
+
+#[test]
+fn experimental() {
+    let tier3 = |name: &str, experimental| TargetInfo {
+        name: name.to_owned(),
+        experimental,
+        metadata: RustcTargetMetadata { tier: Some(3), ..Default::default() },
+        ..Default::default()
+    };
+    let targets = [tier3("cat-unknown-none", true), tier3("dog-unknown-none", false)];
+    let table =
+        super::TierTable { filter: |target| target.metadata.tier == Some(3), columns: vec![] };
+
+    let rendered = super::render_table(&targets, table, None, &RenderOptions::default()).unwrap();
+    assert_eq!(
+        rendered,
+        "[`dog-unknown-none`](platform-support/targets/dog-unknown-none.md) | unknown\n\
+        [`cat-unknown-none`](platform-support/targets/cat-unknown-none.md) *(experimental)* | unknown"
+    );
+
+    let doc = super::render_target_md(&targets[0], &RenderOptions::default());
+    assert!(doc.contains("> [!WARNING]\n> This target is experimental, even within its tier.\n"));
+    assert!(
+        !super::render_target_md(&targets[1], &RenderOptions::default()).contains("experimental")
+    );
+}
+
+#[test]
+fn summary_missing_target() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-summary-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures.join("src"), &dir).unwrap();
+    // The fixture's platform-support.md defines a footnote that must be referenced.
+    let targets =
+        ["aarch64-unknown-linux-gnu", "x86_64-unknown-linux-gnu"].map(|name| TargetInfo {
+            name: name.to_owned(),
+            metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+            footnotes: vec![Footnote::Shared("glibc".to_owned())],
+            ..Default::default()
+        });
+
+    // A SUMMARY.md without the markers, with the entries written by hand.
+    let summary = "\
+- [Platform Support](platform-support.md)
+    - [Targets](platform-support/targets.md)
+      - [aarch64-unknown-linux-gnu](platform-support/targets/aarch64-unknown-linux-gnu.md)
+    - [Targets seeking maintainers](platform-support/seeking-maintainers.md)
+";
+    std::fs::write(dir.join("SUMMARY.md"), summary).unwrap();
+    let mut writer = crate::output::OutputWriter::new(true);
+    let options = RenderOptions::default();
+    let missing = super::render_static(&mut writer, &dir, &targets, &options).unwrap_err();
+    let listed = super::render_static(&mut writer, &dir, &targets[..1], &options);
+    let summary = summary.lines().filter(|line| !line.contains("seeking")).collect::<Vec<_>>();
+    let summary = summary.join("\n");
+    std::fs::write(dir.join("SUMMARY.md"), summary).unwrap();
+    let no_seeking = super::render_static(&mut writer, &dir, &targets[..1], &options).unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        missing.to_string(),
+        "SUMMARY.md does not reference the target pages of x86_64-unknown-linux-gnu"
+    );
+    listed.unwrap();
+    assert_eq!(
+        no_seeking.to_string(),
+        "SUMMARY.md does not reference platform-support/seeking-maintainers.md"
+    );
+}
+
+#[test]
+fn vendor_and_env() {
+    let cfgs = |cfgs: &[(&str, &str)]| {
+        cfgs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    };
+    let gnu = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        target_cfgs: cfgs(&[("target_env", "\"gnu\""), ("target_vendor", "\"unknown\"")]),
+        ..Default::default()
+    };
+    let no_env = TargetInfo {
+        name: "aarch64-apple-darwin".to_owned(),
+        target_cfgs: cfgs(&[("target_env", "\"\""), ("target_vendor", "\"apple\"")]),
+        ..Default::default()
+    };
+    let no_cfgs = TargetInfo { name: "cat-unknown-none".to_owned(), ..Default::default() };
+
+    assert!(super::render_target_md(&gnu, &RenderOptions::default())
+        .contains("**vendor: unknown**\n\n**env: gnu**\n\n"));
+    assert!(super::render_target_md(&no_env, &RenderOptions::default())
+        .contains("**vendor: apple**\n\n**env: none**\n\n"));
+    assert!(super::render_target_md(&no_cfgs, &RenderOptions::default())
+        .contains("**vendor: none**\n\n**env: none**\n\n"));
+}
+
+#[test]
+fn consistent_target_links() {
+    let name = "x86_64-unknown-linux-gnu";
+    let targets = [TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        ..Default::default()
+    }];
+    let table =
+        super::TierTable { filter: |target| target.metadata.tier == Some(1), columns: vec![] };
+
+    let options = RenderOptions::default();
+    assert_eq!(super::target_page_path(name, &options), "targets/x86_64-unknown-linux-gnu.md");
+    assert_eq!(
+        super::target_page_src_path(name, &options),
+        "platform-support/targets/x86_64-unknown-linux-gnu.md"
+    );
+
+    let list = super::render_target_list(&targets, |t| super::target_page_path(t, &options));
+    assert_eq!(list, format!("- [{name}]({})", super::target_page_path(name, &options)));
+
+    let summary_list =
+        super::render_target_list(&targets, |t| super::target_page_src_path(t, &options));
+    assert_eq!(
+        summary_list,
+        format!("- [{name}]({})", super::target_page_src_path(name, &options))
+    );
+
+    let table = super::render_table(&targets, table, None, &RenderOptions::default()).unwrap();
+    assert!(
+        table.starts_with(&format!("[`{name}`]({})", super::target_page_src_path(name, &options)))
+    );
+}
+
+#[test]
+fn stabilized_in() {
+    let target = TargetInfo {
+        name: "cat-unknown-linux-gnu".to_owned(),
+        stabilized_in: Some("1.74".to_owned()),
+        ..Default::default()
+    };
+
+    assert!(super::render_target_md(&target, &RenderOptions::default())
+        .contains("**Available since Rust 1.74**\n\n"));
+}
+
+#[test]
+fn arch_pages() {
+    let target = |name: &str, arch: &str, tier| TargetInfo {
+        name: name.to_owned(),
+        target_cfgs: vec![("target_arch".to_owned(), format!("\"{arch}\""))],
+        metadata: RustcTargetMetadata { tier: Some(tier), ..Default::default() },
+        ..Default::default()
+    };
+    let targets = [
+        target("x86_64-unknown-linux-gnu", "x86_64", 1),
+        target("aarch64-unknown-linux-gnu", "aarch64", 1),
+        target("x86_64-unknown-freebsd", "x86_64", 2),
+    ];
+
+    let pages = super::render_arch_pages(&targets, &RenderOptions::default());
+
+    assert_eq!(
+        pages.iter().map(|(arch, _)| arch.as_str()).collect::<Vec<_>>(),
+        ["aarch64", "x86_64"]
+    );
+    assert_eq!(
+        pages[1].1,
+        "# x86_64\n\nTargets for the `x86_64` architecture.\n\n| Target | Tier |\n|--------|------|\n\
+        | [`x86_64-unknown-linux-gnu`](../targets/x86_64-unknown-linux-gnu.md) | 1 |\n\
+        | [`x86_64-unknown-freebsd`](../targets/x86_64-unknown-freebsd.md) | 2 |\n"
+    );
+}
+
+#[test]
+fn arch_pages_in_summary() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-arch-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures.join("src"), &dir).unwrap();
+    let targets = [TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        target_cfgs: vec![("target_arch".to_owned(), "\"x86_64\"".to_owned())],
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        footnotes: vec![Footnote::Shared("glibc".to_owned())],
+        ..Default::default()
+    }];
+
+    let mut writer = crate::output::OutputWriter::new(false);
+    let options = RenderOptions { arch_pages: true, ..Default::default() };
+    super::render_static(&mut writer, &dir, &targets, &options).unwrap();
+    let summary = std::fs::read_to_string(dir.join("SUMMARY.md")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(summary.contains(
+        "      - [x86_64-unknown-linux-gnu](platform-support/targets/x86_64-unknown-linux-gnu.md)\n\
+        \x20     - [x86_64](platform-support/arch/x86_64.md)\n\
+        \x20   - [Targets seeking maintainers](platform-support/seeking-maintainers.md)\n"
+    ));
+}
+
+#[test]
+fn scoped_footnotes() {
+    let target = |name: &str, content: &str| TargetInfo {
+        name: name.to_owned(),
+        footnotes: vec![
+            Footnote::Shared("x86_32-floats-return-ABI".to_owned()),
+            Footnote::Scoped { name: "1".to_owned(), content: content.to_owned() },
+        ],
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        ..Default::default()
+    };
+    let targets =
+        [target("i686-pc-windows-gnu", "MinGW."), target("i686-unknown-linux-gnu", "glibc.")];
+    let table =
+        super::TierTable { filter: |target| target.metadata.tier == Some(1), columns: vec![] };
+
+    let rendered = super::render_table(&targets, table, None, &RenderOptions::default()).unwrap();
+
+    assert_eq!(
+        rendered,
+        "[`i686-pc-windows-gnu`](platform-support/targets/i686-pc-windows-gnu.md) | unknown [^x86_32-floats-return-ABI] [^i686-pc-windows-gnu-1]
+[`i686-unknown-linux-gnu`](platform-support/targets/i686-unknown-linux-gnu.md) | unknown [^x86_32-floats-return-ABI] [^i686-unknown-linux-gnu-1]
+
+[^i686-pc-windows-gnu-1]: MinGW.
+[^i686-unknown-linux-gnu-1]: glibc."
+    );
+}
+
+#[test]
+fn min_versions() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        min_versions: vec![("glibc".to_owned(), "2.17".to_owned())],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+
+    assert!(doc.contains(
+        "## Minimum versions\n| Component | Minimum version |\n|-----------|-----------------|\n| glibc | 2.17 |\n\n"
+    ));
+    assert!(!super::render_target_md(&TargetInfo::default(), &RenderOptions::default())
+        .contains("## Minimum versions"));
+}
+
+#[test]
+fn no_maintainers_message() {
+    let target = TargetInfo { name: "x86_64-unknown-none".to_owned(), ..Default::default() };
+
+    let options = RenderOptions {
+        no_maintainers_message: Some("Looking for maintainers.".to_owned()),
+        ..Default::default()
+    };
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.contains("## Maintainers\nLooking for maintainers.\n"));
+
+    let options = RenderOptions { no_maintainers_alert: true, ..options };
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.contains("## Maintainers\n> [!WARNING]\n> Looking for maintainers.\n"));
+}
+
+#[test]
+fn custom_columns() {
+    let targets = [TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        maintainers: vec!["@a".to_owned(), "@b".to_owned()],
+        target_cfgs: vec![("target_arch".to_owned(), "\"x86_64\"".to_owned())],
+        metadata: RustcTargetMetadata { tier: Some(1), std: Some(true), ..Default::default() },
+        ..Default::default()
+    }];
+    let table = super::TierTable {
+        filter: |target| target.metadata.tier == Some(1),
+        columns: vec![
+            super::TableColumn {
+                name: "arch",
+                value: |target, _| target.cfg_value("target_arch").unwrap_or("?").to_owned(),
+            },
+            super::STD_COLUMN,
+            super::TableColumn {
+                name: "maintainers",
+                value: |target, _| target.maintainers.len().to_string(),
+            },
+        ],
+    };
+
+    let rendered = super::render_table(&targets, table, None, &RenderOptions::default()).unwrap();
+
+    assert_eq!(
+        rendered,
+        "[`x86_64-unknown-linux-gnu`](platform-support/targets/x86_64-unknown-linux-gnu.md) | x86_64 | ✓ | 2 | unknown"
+    );
+
+    let table = super::TierTable {
+        filter: |_| true,
+        columns: vec![super::TableColumn { name: "broken", value: |_, _| "a | b".to_owned() }],
+    };
+    assert!(super::render_table(&targets, table, None, &RenderOptions::default()).is_err());
+}
+
+#[test]
+fn maintainers_column() {
+    let targets = [TargetInfo {
+        name: "riscv32imac-unknown-none-elf".to_owned(),
+        maintainers: vec!["@a".to_owned(), "@b".to_owned()],
+        metadata: RustcTargetMetadata { tier: Some(3), ..Default::default() },
+        ..Default::default()
+    }];
+    let table = super::TierTable { filter: |_| true, columns: vec![super::MAINTAINERS_COLUMN] };
+    let options = RenderOptions { page_extension: Some("html".to_owned()), ..Default::default() };
+
+    assert_eq!(
+        super::render_table(&targets, table, None, &options).unwrap(),
+        "[`riscv32imac-unknown-none-elf`](platform-support/targets/riscv32imac-unknown-none-elf.html) \
+         | [2](platform-support/targets/riscv32imac-unknown-none-elf.html#maintainers) | unknown"
+    );
+}
+
+#[test]
+fn alert() {
+    assert_eq!(
+        super::render_alert(super::AlertKind::Warning, "Here be dragons.\n\nReally."),
+        "> [!WARNING]\n> Here be dragons.\n>\n> Really.\n\n"
+    );
+
+    let no_std = TargetInfo {
+        name: "thumbv7em-none-eabi".to_owned(),
+        metadata: RustcTargetMetadata { std: Some(false), ..Default::default() },
+        ..Default::default()
+    };
+    assert!(super::render_target_md(&no_std, &RenderOptions::default())
+        .contains("> [!NOTE]\n> This target does not support `std`"));
+}
+
+#[test]
+fn page_template() {
+    let target = TargetInfo {
+        name: "cat-unknown-linux-gnu".to_owned(),
+        maintainers: vec!["@cat".to_owned()],
+        sections: vec![("Testing".to_owned(), "Pet the cat.".to_owned())],
+        metadata: RustcTargetMetadata { tier: Some(3), ..Default::default() },
+        ..Default::default()
+    };
+    let template = "# {{name}} (tier {{ tier }})\n\n{{section:Testing}}\n\n{{section:Overview}}\n\n{{maintainers}}\n";
+
+    assert_eq!(
+        super::render_target_md_with_template(&target, template, &RenderOptions::default()).unwrap(),
+        "# cat-unknown-linux-gnu (tier 3)\n\nPet the cat.\n\nUnknown.\n\nThis target is maintained by:\n- [@cat](https://github.com/cat)\n"
+    );
+    assert!(super::render_target_md_with_template(
+        &target,
+        "{{section:Meowing}}",
+        &RenderOptions::default()
+    )
+    .is_err());
+    assert!(super::render_target_md_with_template(&target, "{{purr}}", &RenderOptions::default())
+        .is_err());
+    assert!(super::render_target_md_with_template(&target, "{{name", &RenderOptions::default())
+        .is_err());
+}
+
+#[test]
+fn abi_notes() {
+    let mut target = TargetInfo {
+        name: "armv7-unknown-linux-gnueabihf".to_owned(),
+        abi_notes: Some("Floats are passed in VFP registers.".to_owned()),
+        ..Default::default()
+    };
+    assert!(super::render_target_md(&target, &RenderOptions::default())
+        .contains("## ABI\nFloats are passed in VFP registers.\n\n"));
+
+    target.target_cfgs = vec![("target_abi".to_owned(), "\"eabihf\"".to_owned())];
+    assert!(super::render_target_md(&target, &RenderOptions::default()).contains(
+        "## ABI\nThis target uses the `eabihf` ABI.\n\nFloats are passed in VFP registers.\n\n"
+    ));
+
+    assert!(!super::render_target_md(&TargetInfo::default(), &RenderOptions::default())
+        .contains("## ABI"));
+}
+
+#[test]
+fn ci_link() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        ci: Some(crate::parse::CiLink {
+            url: "https://github.com/rust-lang/rust/actions".to_owned(),
+            label: "dist-x86_64-linux".to_owned(),
+        }),
+        ..Default::default()
+    };
+
+    assert!(super::render_target_md(&target, &RenderOptions::default())
+        .contains("**CI: [dist-x86_64-linux](https://github.com/rust-lang/rust/actions)**\n\n"));
+}
+
+#[test]
+fn toolchain_requirements() {
+    let requirement = |tool: &str, min_version: &str| ToolchainRequirement {
+        tool: tool.to_owned(),
+        min_version: min_version.to_owned(),
+    };
+    let mut target = TargetInfo {
+        name: "aarch64-apple-ios".to_owned(),
+        toolchain_requirements: vec![requirement("Xcode", "14.0"), requirement("LLVM", "17")],
+        ..Default::default()
+    };
+
+    let table =
+        "| Tool | Minimum version |\n|------|-----------------|\n| Xcode | 14.0 |\n| LLVM | 17 |";
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(&format!("## Requirements\n{table}\n\n## ")));
+
+    target.sections = vec![("Requirements".to_owned(), "An Apple device.".to_owned())];
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(&format!("## Requirements\n{table}\n\nAn Apple device.\n\n")));
+}
+
+#[test]
+fn partial_tables() {
+    let targets = [TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        ..Default::default()
+    }];
+    let content = ["TIER1HOST", "TIER2HOST", "TIER2", "TIER3"]
+        .map(|name| format!("<!-- {name} SECTION START --><!-- {name} SECTION END -->\n"))
+        .concat();
+    let options = RenderOptions { partial: true, ..Default::default() };
+
+    let rendered = super::render_platform_support_tables(&content, &targets, &options).unwrap();
+    assert!(rendered.starts_with(
+        "[`x86_64-unknown-linux-gnu`](platform-support/targets/x86_64-unknown-linux-gnu.md) | unknown\n\n\
+        > [!NOTE]\n> This table only lists some of the targets.\n"
+    ));
+}
+
+#[test]
+fn proposals() {
+    let proposal =
+        |title: &str, url: &str| Proposal { title: title.to_owned(), url: url.to_owned() };
+    let target = TargetInfo {
+        name: "cat-unknown-none".to_owned(),
+        proposals: vec![
+            proposal(
+                "MCP #1: Add cat targets",
+                "https://github.com/rust-lang/compiler-team/issues/1",
+            ),
+            proposal("RFC 2: Cats", "https://rust-lang.github.io/rfcs/0002-cats.html"),
+        ],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(
+        "## Related proposals\n\
+        - [MCP #1: Add cat targets](https://github.com/rust-lang/compiler-team/issues/1)\n\
+        - [RFC 2: Cats](https://rust-lang.github.io/rfcs/0002-cats.html)\n\n"
+    ));
+    assert!(!super::render_target_md(&TargetInfo::default(), &RenderOptions::default())
+        .contains("## Related proposals"));
+}
+
+#[test]
+fn resolution() {
+    let pattern = |pattern: &str| ParsedTargetInfoFile {
+        pattern: pattern.to_owned(),
+        maintainers: vec!["@someone".to_owned()],
+        ..Default::default()
+    };
+    let linux = pattern("*-linux-*");
+    let x86_64 = pattern("x86_64-*");
+    let target = |name: &str, maintainers: usize| TargetInfo {
+        name: name.to_owned(),
+        maintainers: vec!["@someone".to_owned(); maintainers],
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        ..Default::default()
+    };
+    let targets = [
+        target("x86_64-unknown-linux-gnu", 2),
+        target("aarch64-unknown-linux-gnu", 1),
+        TargetInfo { name: "cat-unknown-none".to_owned(), ..Default::default() },
+    ];
+
+    assert_eq!(
+        super::render_resolution(&[linux, x86_64], &targets),
+        "| Target | Patterns | Tier | Maintainers |
+|--------|----------|------|-------------|
+| x86_64-unknown-linux-gnu | `*-linux-*`, `x86_64-*` | 1 | 2 |
+| aarch64-unknown-linux-gnu | `*-linux-*` | 1 | 1 |
+| cat-unknown-none | none | UNKNOWN | 0 |
+"
+    );
+}
+
+#[test]
+fn draft_sections() {
+    let target = TargetInfo {
+        name: "cat-unknown-none".to_owned(),
+        sections: vec![("Overview".to_owned(), "Work in progress.".to_owned())],
+        draft_sections: vec!["Overview".to_owned()],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains("## Overview\nUnknown.\n\n"));
+
+    let options = RenderOptions { include_drafts: true, ..Default::default() };
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.contains("## Overview\nWork in progress.\n\n"));
+}
+
+#[test]
+fn unusual_cfgs() {
+    let target = |name: &str, endian: &str| TargetInfo {
+        name: name.to_owned(),
+        target_cfgs: vec![
+            ("target_endian".to_owned(), format!("\"{endian}\"")),
+            ("target_pointer_width".to_owned(), "\"64\"".to_owned()),
+        ],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(
+        &target("s390x-unknown-linux-gnu", "big"),
+        &RenderOptions::default(),
+    );
+    assert!(doc.contains(
+        "> [!NOTE]\n> This target is big-endian.\n> Code written for other targets may wrongly assume otherwise.\n"
+    ));
+
+    let doc = super::render_target_md(
+        &target("x86_64-unknown-linux-gnu", "little"),
+        &RenderOptions::default(),
+    );
+    assert!(!doc.contains("> [!NOTE]"));
+}
+
+#[test]
+fn binary_size_notes() {
+    let target = TargetInfo {
+        name: "thumbv7em-none-eabi".to_owned(),
+        binary_size_notes: Some("Use `opt-level = \"z\"` and `panic = \"abort\"`.\n".to_owned()),
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains("## Binary size\nUse `opt-level = \"z\"` and `panic = \"abort\"`.\n\n"));
+    assert!(!super::render_target_md(&TargetInfo::default(), &RenderOptions::default())
+        .contains("## Binary size"));
+}
+
+#[test]
+fn maintainers_csv() {
+    let targets = [
+        TargetInfo {
+            name: "x86_64-unknown-linux-gnu".to_owned(),
+            maintainers: vec!["@someone".to_owned(), "\"Doe, Jane \"JD\"\"".to_owned()],
+            metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+            ..Default::default()
+        },
+        TargetInfo { name: "cat-unknown-none".to_owned(), ..Default::default() },
+        TargetInfo {
+            name: "dog-unknown-none".to_owned(),
+            maintainers: vec!["@dog".to_owned()],
+            ..Default::default()
+        },
+    ];
+
+    assert_eq!(
+        super::render_maintainers_csv(&targets),
+        "target,tier,maintainer
+x86_64-unknown-linux-gnu,1,@someone
+x86_64-unknown-linux-gnu,1,\"\"\"Doe, Jane \"\"JD\"\"\"\"\"
+dog-unknown-none,,@dog
+"
+    );
+}
+
+#[test]
+fn tier_policy_link() {
+    let target = TargetInfo {
+        name: "thumbv7em-none-eabi".to_owned(),
+        metadata: RustcTargetMetadata { tier: Some(2), ..Default::default() },
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains("\n**[Tier 2](../../target-tier-policy.md#tier-2-target-policy)**\n"));
+
+    let options = RenderOptions {
+        tier_policy_url: Some("https://doc.rust-lang.org/rustc/target-tier-policy.html".to_owned()),
+        ..Default::default()
+    };
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.contains(
+        "\n**[Tier 2](https://doc.rust-lang.org/rustc/target-tier-policy.html#tier-2-target-policy)**\n"
+    ));
+
+    let doc = super::render_target_md(&TargetInfo::default(), &RenderOptions::default());
+    assert!(doc.contains("\n**Tier: UNKNOWN**\n"));
+}
+
+#[test]
+fn build_example() {
+    let mut target = TargetInfo {
+        name: "wasm32-wasip1".to_owned(),
+        build_example: Some("./x.py build --target wasm32-wasip1\n".to_owned()),
+        ..Default::default()
+    };
+
+    let block = "```bash\n./x.py build --target wasm32-wasip1\n```";
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(&format!("## Building the target\n{block}\n\n")));
+
+    target.sections = vec![("Building the target".to_owned(), "Needs a WASI SDK.".to_owned())];
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(&format!("## Building the target\nNeeds a WASI SDK.\n\n{block}\n\n")));
+}
+
+#[test]
+fn checklist_requirements() {
+    let target = TargetInfo {
+        name: "aarch64-apple-ios".to_owned(),
+        sections: vec![(
+            "Requirements".to_owned(),
+            "You need a Mac.\n\n- Xcode command line tools\n- An iOS device".to_owned(),
+        )],
+        min_versions: vec![("iOS".to_owned(), "12.0".to_owned())],
+        toolchain_requirements: vec![ToolchainRequirement {
+            tool: "Xcode".to_owned(),
+            min_version: "14.0".to_owned(),
+        }],
+        ..Default::default()
+    };
+    let options = RenderOptions { checklist_requirements: true, ..Default::default() };
+
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.contains(
+        "## Requirements\nYou need a Mac.\n\n\
+        - [ ] iOS 12.0 or newer\n\
+        - [ ] Xcode 14.0 or newer\n\
+        - [ ] Xcode command line tools\n\
+        - [ ] An iOS device\n\n"
+    ));
+    assert!(!doc.contains("## Minimum versions"));
+}
+
+#[test]
+fn nightly_tier() {
+    let target = |nightly_tier| TargetInfo {
+        name: "aarch64-unknown-linux-musl".to_owned(),
+        nightly_tier,
+        metadata: RustcTargetMetadata { tier: Some(2), ..Default::default() },
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target(Some(1)), &RenderOptions::default());
+    assert!(doc
+        .contains("**[Tier 2](../../target-tier-policy.md#tier-2-target-policy)**\n\n**std: ?**"));
+    assert!(doc.contains(
+        "> [!NOTE]\n> This target is tier 1 on nightly. \
+        It was promoted after the current stable release, which has the tier above.\n"
+    ));
+
+    let doc = super::render_target_md(&target(Some(2)), &RenderOptions::default());
+    assert!(!doc.contains("on nightly"));
+}
+
+#[test]
+fn page_extension() {
+    let name = "x86_64-unknown-linux-gnu";
+    let targets = [TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        ..Default::default()
+    }];
+    let options =
+        RenderOptions { page_extension: Some(".markdown".to_owned()), ..Default::default() };
+    let table =
+        super::TierTable { filter: |target| target.metadata.tier == Some(1), columns: vec![] };
+
+    let path = "platform-support/targets/x86_64-unknown-linux-gnu.markdown";
+    assert_eq!(super::target_page_src_path(name, &options), path);
+    assert_eq!(
+        super::render_target_list(&targets, |t| super::target_page_path(t, &options)),
+        format!("- [{name}](targets/{name}.markdown)")
+    );
+    assert!(super::render_table(&targets, table, None, &options)
+        .unwrap()
+        .starts_with(&format!("[`{name}`]({path})")));
+    assert_eq!(
+        super::missing_summary_entries(&format!("- [{name}]({path})"), &targets, &options),
+        Vec::<&str>::new()
+    );
+}
+
+#[test]
+fn see_also() {
+    let target = TargetInfo {
+        name: "aarch64-unknown-linux-gnu".to_owned(),
+        see_also: vec!["aarch64-unknown-linux-musl".to_owned()],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(
+        "## See also\n- [aarch64-unknown-linux-musl](../targets/aarch64-unknown-linux-musl.md)\n\n"
+    ));
+}
+
+#[test]
+fn tested_by() {
+    let tested_by = |platform: &str, runner: &str| TestedBy {
+        platform: platform.to_owned(),
+        runner: runner.to_owned(),
+        url: format!("https://github.com/rust-lang/rust/actions?query={runner}"),
+    };
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        sections: vec![("Testing".to_owned(), "Tested in CI.".to_owned())],
+        tested_by: vec![
+            tested_by("x86_64 Linux", "dist-x86_64-linux"),
+            tested_by("x86_64 Linux", "x86_64-gnu"),
+        ],
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains(
+        "## Testing\nTested in CI.\n\n| Platform | CI runner |\n|----------|-----------|\n\
+        | x86_64 Linux | [dist-x86_64-linux](https://github.com/rust-lang/rust/actions?query=dist-x86_64-linux) |\n\
+        | x86_64 Linux | [x86_64-gnu](https://github.com/rust-lang/rust/actions?query=x86_64-gnu) |\n\n"
+    ));
+}
+
+#[test]
+fn seeking_maintainers() {
+    let targets = [
+        TargetInfo {
+            name: "sparc-unknown-none-elf".to_owned(),
+            maintenance_status: Some(MaintenanceStatus::SeekingMaintainers),
+            metadata: RustcTargetMetadata { tier: Some(3), ..Default::default() },
+            ..Default::default()
+        },
+        TargetInfo {
+            name: "x86_64-unknown-linux-gnu".to_owned(),
+            maintenance_status: Some(MaintenanceStatus::ActivelyMaintained),
+            metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+            ..Default::default()
+        },
+    ];
+
+    let page = super::render_seeking_maintainers_page(&targets, &RenderOptions::default());
+    assert!(page.starts_with("# Targets seeking maintainers\n\n"));
+    assert!(page.ends_with(
+        "| Target | Tier |\n|--------|------|\n\
+        | [`sparc-unknown-none-elf`](targets/sparc-unknown-none-elf.md) | 3 |\n"
+    ));
+
+    let badge = super::render_target_md(&targets[0], &RenderOptions::default());
+    assert!(badge.contains("**Maintenance: seeking maintainers**\n\n"));
+
+    let page = super::render_seeking_maintainers_page(&targets[1..], &RenderOptions::default());
+    assert!(page.ends_with("No targets are currently seeking maintainers.\n"));
+}
+
+#[test]
+fn split_tables() {
+    let target = |name: &str, arch: &str| TargetInfo {
+        name: name.to_owned(),
+        target_cfgs: vec![("target_arch".to_owned(), format!("\"{arch}\""))],
+        metadata: RustcTargetMetadata { tier: Some(3), ..Default::default() },
+        ..Default::default()
+    };
+    let targets = [
+        target("x86_64-unknown-haiku", "x86_64"),
+        target("aarch64-unknown-redox", "aarch64"),
+        target("x86_64-unknown-redox", "x86_64"),
+    ];
+    let content = ["TIER1HOST", "TIER2HOST", "TIER2"]
+        .map(|name| format!("<!-- {name} SECTION START --><!-- {name} SECTION END -->\n"))
+        .concat()
+        + "## Tier 3\n\ntarget | std | host | maintainers | notes\n-------|:---:|:----:|:-----------:|-------\n<!-- TIER3 SECTION START -->\n<!-- TIER3 SECTION END -->\n";
+
+    let render = |split_tables_over| {
+        let options =
+            RenderOptions { split_tables_over: Some(split_tables_over), ..Default::default() };
+        super::render_platform_support_tables(&content, &targets, &options).unwrap()
+    };
+
+    assert!(render(3).ends_with(
+        "## Tier 3\n\ntarget | std | host | maintainers | notes\n-------|:---:|:----:|:-----------:|-------\n\
+        [`x86_64-unknown-haiku`](platform-support/targets/x86_64-unknown-haiku.md) | ? | ? | [0](platform-support/targets/x86_64-unknown-haiku.md#maintainers) | unknown\n\
+        [`aarch64-unknown-redox`](platform-support/targets/aarch64-unknown-redox.md) | ? | ? | [0](platform-support/targets/aarch64-unknown-redox.md#maintainers) | unknown\n\
+        [`x86_64-unknown-redox`](platform-support/targets/x86_64-unknown-redox.md) | ? | ? | [0](platform-support/targets/x86_64-unknown-redox.md#maintainers) | unknown\n"
+    ));
+    assert!(render(2).ends_with(
+        "## Tier 3\n\n\
+        - [`aarch64`](#tier3-aarch64)\n\
+        - [`x86_64`](#tier3-x86_64)\n\n\
+        #### `aarch64` {#tier3-aarch64}\n\n\
+        target | std | host | maintainers | notes\n-------|:---:|:----:|:-----------:|-------\n\
+        [`aarch64-unknown-redox`](platform-support/targets/aarch64-unknown-redox.md) | ? | ? | [0](platform-support/targets/aarch64-unknown-redox.md#maintainers) | unknown\n\n\
+        #### `x86_64` {#tier3-x86_64}\n\n\
+        target | std | host | maintainers | notes\n-------|:---:|:----:|:-----------:|-------\n\
+        [`x86_64-unknown-haiku`](platform-support/targets/x86_64-unknown-haiku.md) | ? | ? | [0](platform-support/targets/x86_64-unknown-haiku.md#maintainers) | unknown\n\
+        [`x86_64-unknown-redox`](platform-support/targets/x86_64-unknown-redox.md) | ? | ? | [0](platform-support/targets/x86_64-unknown-redox.md#maintainers) | unknown\n"
+    ));
+}
+
+#[test]
+fn tier3_header_without_maintainers() {
+    let content = ["TIER1HOST", "TIER2HOST", "TIER2"]
+        .map(|name| format!("<!-- {name} SECTION START --><!-- {name} SECTION END -->\n"))
+        .concat()
+        + "target | std | host | notes\n-------|:---:|:----:|-------\n<!-- TIER3 SECTION START -->\n<!-- TIER3 SECTION END -->\n";
+
+    let err = super::render_platform_support_tables(&content, &[], &RenderOptions::default());
+
+    assert_eq!(
+        err.unwrap_err().to_string(),
+        "the table before <!-- TIER3 SECTION START --> in platform-support.md has 4 columns, \
+        but needs 5: target | std | host | maintainers | notes"
+    );
+}
+
+#[test]
+fn collapsed_cfgs() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        target_cfgs: vec![
+            ("target_arch".to_owned(), "\"x86_64\"".to_owned()),
+            ("target_os".to_owned(), "\"linux\"".to_owned()),
+        ],
+        ..Default::default()
+    };
+    let list = "- `target_arch` = `\"x86_64\"`\n- `target_os` = `\"linux\"`";
+    let options = RenderOptions { flat_cfg: true, ..Default::default() };
+
+    let inline = super::render_target_md(&target, &options);
+    assert!(inline.ends_with(&format!("target-specific cfg values:\n{list}\n\n")));
+
+    let options = RenderOptions { cfg_collapse_threshold: Some(1), ..options };
+    let collapsed = super::render_target_md(&target, &options);
+    assert!(collapsed.ends_with(&format!(
+        "target-specific cfg values:\n<details><summary>Target cfg values</summary>\n\n{list}\n\n</details>\n\n"
+    )));
+}
+
+#[test]
+fn family_outlier_marker() {
+    let target = |name: &str, std, family_outliers| TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata { std: Some(std), ..Default::default() },
+        family_outliers,
+        ..Default::default()
+    };
+    let targets = [
+        target("aarch64-unknown-linux-gnu", true, vec![]),
+        target("cat-unknown-linux-gnu", false, vec!["std"]),
+    ];
+    let table = super::TierTable { filter: |_| true, columns: vec![super::STD_COLUMN] };
+
+    let rendered = super::render_table(&targets, table, None, &RenderOptions::default()).unwrap();
+    assert_eq!(
+        rendered,
+        "[`aarch64-unknown-linux-gnu`](platform-support/targets/aarch64-unknown-linux-gnu.md) | ✓ | unknown\n\
+        [`cat-unknown-linux-gnu`](platform-support/targets/cat-unknown-linux-gnu.md) |   ⚠ | unknown"
+    );
+}
+
+#[test]
+fn canonical_url() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        see_also: vec!["x86_64-unknown-linux-musl".to_owned()],
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        ..Default::default()
+    };
+    let options = RenderOptions {
+        base_url: Some("https://doc.rust-lang.org/nightly/rustc/".to_owned()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        super::canonical_url(&target.name, &options).unwrap(),
+        "https://doc.rust-lang.org/nightly/rustc/platform-support/targets/x86_64-unknown-linux-gnu.html"
+    );
+    assert_eq!(super::canonical_url(&target.name, &RenderOptions::default()), None);
+
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.starts_with(
+        "<!-- canonical: https://doc.rust-lang.org/nightly/rustc/platform-support/targets/x86_64-unknown-linux-gnu.html -->\n\
+        # x86_64-unknown-linux-gnu\n\n\
+        **[Tier 1](https://doc.rust-lang.org/nightly/rustc/target-tier-policy.html#tier-1-target-policy)**"
+    ));
+    assert!(doc.contains(
+        "- [x86_64-unknown-linux-musl](https://doc.rust-lang.org/nightly/rustc/platform-support/targets/x86_64-unknown-linux-musl.html)"
+    ));
+}
+
+#[test]
+fn maintainer_avatars() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        maintainers: vec!["@penguin".to_owned(), "\"The Penguins\"".to_owned()],
+        ..Default::default()
+    };
+    let options = RenderOptions { maintainer_avatars: true, ..Default::default() };
+
+    assert_eq!(
+        super::render_maintainers_content(&target, &options),
+        "This target is maintained by:\n\
+        - <img src=\"https://github.com/penguin.png?size=40\" alt=\"\" width=\"20\" height=\"20\"> \
+        [@penguin](https://github.com/penguin)\n\
+        - \"The Penguins\""
+    );
+    assert!(!super::render_maintainers_content(&target, &RenderOptions::default()).contains("<img"));
+}
+
+#[test]
+fn rustc_unavailable() {
+    let target = TargetInfo {
+        name: "cat-unknown-linux-gnu".to_owned(),
+        rustc_unavailable: true,
+        ..Default::default()
+    };
+
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains("*Information from rustc is unavailable for this target.*\n\n"));
+    assert!(doc.contains("The cfg values of this target are unavailable, rustc failed for it.\n"));
+    assert!(!doc.contains("target-specific cfg values"));
+}
+
+#[test]
+fn optional_sections() {
+    let mut target = TargetInfo { name: "cat-unknown-linux-gnu".to_owned(), ..Default::default() };
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains("## Building the target\nUnknown.\n"));
+    assert!(!doc.contains("## Cross compilation"));
+
+    target.sections.push(("Cross compilation".to_owned(), "Use `cross`.".to_owned()));
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.contains("## Cross compilation\nUse `cross`.\n"));
+
+    // A draft optional section is left out like a missing one.
+    target.draft_sections.push("Cross compilation".to_owned());
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(!doc.contains("## Cross compilation"));
+}
+
+#[test]
+fn unreferenced_footnotes() {
+    let targets = [TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        metadata: RustcTargetMetadata { tier: Some(1), ..Default::default() },
+        footnotes: vec![Footnote::Shared("glibc".to_owned())],
+        ..Default::default()
+    }];
+    let content = ["TIER1HOST", "TIER2HOST", "TIER2", "TIER3"]
+        .map(|name| format!("<!-- {name} SECTION START --><!-- {name} SECTION END -->\n"))
+        .concat();
+    let content = format!("{content}\n[^glibc]: glibc.\n[^musl]: musl.\n");
+
+    let err = super::render_platform_support_tables(&content, &targets, &RenderOptions::default())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "platform-support.md defines footnotes that are never referenced: `[^musl]`"
+    );
+
+    let options = RenderOptions { partial: true, ..Default::default() };
+    assert!(super::render_platform_support_tables(&content, &targets, &options).is_ok());
+}
+
+#[test]
+fn team_maintainers() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        maintainers: vec!["@rust-lang/opsem".to_owned(), "Some One".to_owned()],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        super::render_maintainers_content(&target, &RenderOptions::default()),
+        "This target is maintained by:\n\
+        - [@rust-lang/opsem](https://github.com/orgs/rust-lang/teams/opsem)\n\
+        - Some One"
+    );
+}
+
+#[test]
+fn json() {
+    let targets = [TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        maintainers: vec!["@penguin".to_owned()],
+        sections: vec![
+            ("Overview".to_owned(), "Linux.".to_owned()),
+            ("Testing".to_owned(), "Not yet.".to_owned()),
+        ],
+        draft_sections: vec!["Testing".to_owned()],
+        footnotes: vec![Footnote::Shared("glibc".to_owned())],
+        target_cfgs: vec![("target_arch".to_owned(), "\"x86_64\"".to_owned())],
+        metadata: RustcTargetMetadata {
+            description: Some("64-bit Linux".to_owned()),
+            tier: Some(1),
+            host_tools: Some(true),
+            std: Some(true),
+        },
+        ..Default::default()
+    }];
+
+    let json = super::render_json(&targets, &RenderOptions::default());
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::json!([{
+            "name": "x86_64-unknown-linux-gnu",
+            "tier": 1,
+            "maintainers": ["@penguin"],
+            "sections": { "Overview": "Linux." },
+            "footnotes": ["glibc"],
+            "cfg": [["target_arch", "x86_64"]],
+            "description": "64-bit Linux",
+            "std": true,
+            "host_tools": true,
+        }])
+    );
+}
+
+#[test]
+fn description_tagline() {
+    let mut target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        metadata: RustcTargetMetadata {
+            description: Some("64-bit Linux".to_owned()),
+            tier: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.starts_with("# x86_64-unknown-linux-gnu\n\n*64-bit Linux*\n\n**[Tier 1]("));
+
+    target.metadata.description = None;
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.starts_with("# x86_64-unknown-linux-gnu\n\n**[Tier 1]("));
+}
+
+#[test]
+fn aliases() {
+    let target = TargetInfo {
+        name: "riscv32imac-unknown-none-elf".to_owned(),
+        aliases: vec!["riscv32imac-none".to_owned(), "riscv32-none".to_owned()],
+        ..Default::default()
+    };
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.starts_with(
+        "# riscv32imac-unknown-none-elf\n\n**Also known as:** `riscv32imac-none`, `riscv32-none`\n\n"
+    ));
+}
+
+#[test]
+fn table_of_contents() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        sections: vec![("Building the target".to_owned(), "`./x.py build`".to_owned())],
+        binary_size_notes: Some("Use `opt-level = \"z\"`.".to_owned()),
+        ..Default::default()
+    };
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+
+    let contents = "- [Maintainers](#maintainers)\n\
+        - [Overview](#overview)\n\
+        - [Requirements](#requirements)\n\
+        - [Testing](#testing)\n\
+        - [Building the target](#building-the-target)\n\
+        - [Binary size](#binary-size)\n\
+        - [cfg](#cfg)\n\n## Maintainers\n";
+    assert!(doc.contains(contents), "{doc}");
+    assert_eq!(super::section_anchor("Cross-compilation (C/C++)"), "cross-compilation-cc");
+}
+
+#[test]
+fn cfg_values_with_backticks() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        target_cfgs: vec![
+            ("target_feature".to_owned(), "\"fxsr\"".to_owned()),
+            ("target_feature".to_owned(), "\"sse\"".to_owned()),
+            ("target_abi".to_owned(), "\"a`b\"".to_owned()),
+            ("target_env".to_owned(), "`gnu``".to_owned()),
+        ],
+        ..Default::default()
+    };
+    let options = RenderOptions { flat_cfg: true, ..Default::default() };
+    let doc = super::render_target_md(&target, &options);
+    assert!(doc.contains(
+        "- `target_feature` = `\"fxsr\"`\n\
+        - `target_feature` = `\"sse\"`\n\
+        - `target_abi` = ``\"a`b\"``\n\
+        - `target_env` = ``` `gnu`` ```\n"
+    ));
+}
+
+#[test]
+fn grouped_cfgs() {
+    let target = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        target_cfgs: vec![
+            ("target_arch".to_owned(), "\"x86_64\"".to_owned()),
+            ("target_endian".to_owned(), "\"little\"".to_owned()),
+            ("target_env".to_owned(), "\"gnu\"".to_owned()),
+            ("target_feature".to_owned(), "\"sse\"".to_owned()),
+            ("target_os".to_owned(), "\"linux\"".to_owned()),
+        ],
+        ..Default::default()
+    };
+    let doc = super::render_target_md(&target, &RenderOptions::default());
+    assert!(doc.ends_with(
+        "target-specific cfg values:\n\n\
+        ### Architecture\n\n- `target_arch` = `\"x86_64\"`\n\n\
+        ### OS/env\n\n- `target_env` = `\"gnu\"`\n- `target_os` = `\"linux\"`\n\n\
+        ### Data model\n\n- `target_endian` = `\"little\"`\n\n\
+        ### Other\n\n- `target_feature` = `\"sse\"`\n\n"
+    ));
+}
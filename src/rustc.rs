@@ -0,0 +1,193 @@
+//! Obtaining information about targets from rustc.
+
+use eyre::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    path::Path,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Information about a target obtained from rustc.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RustcTargetInfo {
+    pub target_cfgs: Vec<(String, String)>,
+    pub metadata: RustcTargetMetadata,
+}
+
+/// The `metadata` of a target spec. Serializes back into the same shape as rustc prints it,
+/// with `tier` as a number, so exports can be deserialized again.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RustcTargetMetadata {
+    pub description: Option<String>,
+    pub tier: Option<u8>,
+    pub host_tools: Option<bool>,
+    pub std: Option<bool>,
+}
+
+/// Get information about a target from rustc.
+pub fn rustc_target_info(rustc: &Path, target: &str) -> Result<RustcTargetInfo> {
+    let cfgs = rustc_stdout(rustc, &["--print", "cfg", "--target", target])?;
+    let target_cfgs = parse_cfgs(cfgs.lines());
+
+    #[derive(Deserialize)]
+    struct TargetJson {
+        metadata: RustcTargetMetadata,
+    }
+
+    let json_spec = rustc_stdout(
+        rustc,
+        &["-Zunstable-options", "--print", "target-spec-json", "--target", target],
+    )?;
+    let spec = serde_json::from_str::<TargetJson>(&json_spec)
+        .wrap_err("parsing --print target-spec-json for metadata")?;
+
+    Ok(RustcTargetInfo { target_cfgs, metadata: spec.metadata })
+}
+
+/// Get information about all targets from rustc, in the order of `targets`.
+/// Each target needs two rustc invocations, so the targets are spread across a worker per CPU.
+pub fn rustc_target_infos(rustc: &Path, targets: &[String]) -> Vec<Result<RustcTargetInfo>> {
+    let workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let next = AtomicUsize::new(0);
+    let mut infos = std::thread::scope(|scope| {
+        let workers = (0..workers.min(targets.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut infos = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(target) = targets.get(index) else { break infos };
+                        infos.push((index, rustc_target_info(rustc, target)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect::<Vec<_>>()
+    });
+    infos.sort_by_key(|(index, _)| *index);
+    infos.into_iter().map(|(_, info)| info).collect()
+}
+
+/// The information about targets from one rustc, stored between runs.
+#[derive(Default, Deserialize, Serialize)]
+struct RustcCache {
+    /// The `rustc --version --verbose` the information is from.
+    rustc_version: String,
+    targets: BTreeMap<String, RustcTargetInfo>,
+}
+
+/// Like [`rustc_target_infos`], but only asks rustc about targets missing from the cache file,
+/// which is discarded when it was written by a different rustc, and adds them to it.
+/// Failures aren't cached, so those targets are tried again next time.
+pub fn cached_rustc_target_infos(
+    rustc: &Path,
+    targets: &[String],
+    cache_path: &Path,
+) -> Result<Vec<Result<RustcTargetInfo>>> {
+    let rustc_version = rustc_stdout(rustc, &["--version", "--verbose"])?;
+    // An unreadable cache is treated like an empty one, it's rewritten below.
+    let cache = std::fs::read_to_string(cache_path).ok();
+    let mut cache = cache
+        .and_then(|cache| serde_json::from_str::<RustcCache>(&cache).ok())
+        .filter(|cache| cache.rustc_version == rustc_version)
+        .unwrap_or_else(|| RustcCache { rustc_version, targets: BTreeMap::new() });
+
+    let missing = targets
+        .iter()
+        .filter(|target| !cache.targets.contains_key(*target))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut failures = BTreeMap::new();
+    if !missing.is_empty() {
+        let infos = rustc_target_infos(rustc, &missing);
+        for (target, info) in missing.into_iter().zip(infos) {
+            match info {
+                Ok(info) => {
+                    cache.targets.insert(target, info);
+                }
+                Err(err) => {
+                    failures.insert(target, err);
+                }
+            }
+        }
+        let json = serde_json::to_string(&cache).wrap_err("serializing the rustc cache")?;
+        std::fs::write(cache_path, json)
+            .wrap_err_with(|| format!("writing the rustc cache {}", cache_path.display()))?;
+    }
+
+    Ok(targets
+        .iter()
+        .map(|target| match failures.remove(target) {
+            Some(err) => Err(err),
+            None => Ok(cache.targets[target].clone()),
+        })
+        .collect())
+}
+
+/// Parses the `target_` key-value pairs of `--print cfg` output lines.
+fn parse_cfgs<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<(String, String)> {
+    lines
+        .filter_map(|line| {
+            if line.starts_with("target_") {
+                let Some((key, value)) = line.split_once('=') else {
+                    // For example `unix`
+                    return None;
+                };
+                Some((key.to_owned(), value.to_owned()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// A target in a `--rustc-info` file, as captured from rustc.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DumpedTarget {
+    target: String,
+    /// The lines of `--print cfg`.
+    cfg: Vec<String>,
+    /// The `metadata` of `--print target-spec-json`.
+    metadata: RustcTargetMetadata,
+}
+
+/// Loads pre-captured rustc information for all targets from a JSON file,
+/// which allows building the docs without a working rustc.
+pub fn load_rustc_info_dump(path: &Path) -> Result<Vec<(String, RustcTargetInfo)>> {
+    let content = std::fs::read_to_string(path).wrap_err("reading file")?;
+    let targets = serde_json::from_str::<Vec<DumpedTarget>>(&content).wrap_err("invalid JSON")?;
+
+    Ok(targets
+        .into_iter()
+        .map(|dumped| {
+            let target_cfgs = parse_cfgs(dumped.cfg.iter().map(String::as_str));
+            (dumped.target, RustcTargetInfo { target_cfgs, metadata: dumped.metadata })
+        })
+        .collect())
+}
+
+pub fn rustc_stdout(rustc: &Path, args: &[&str]) -> Result<String> {
+    let command = format!("`rustc {}`", args.join(" "));
+    let output =
+        Command::new(rustc).args(args).output().wrap_err_with(|| format!("running {command}"))?;
+    if !output.status.success() {
+        bail!(
+            "{command} failed: {}, {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).wrap_err_with(|| format!("{command} printed invalid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests;
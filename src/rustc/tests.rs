@@ -0,0 +1,155 @@
+use crate::TargetInfo;
+
+#[test]
+fn generate_from_rustc_info_dump() {
+    let path =
+        std::env::temp_dir().join(format!("target-docs-rustc-info-{}.json", std::process::id()));
+    let dump = r#"[
+        {
+            "target": "x86_64-unknown-linux-gnu",
+            "cfg": ["panic=\"unwind\"", "target_arch=\"x86_64\"", "target_env=\"gnu\"", "unix"],
+            "metadata": { "description": "64-bit Linux", "tier": 1, "host_tools": true, "std": true }
+        },
+        {
+            "target": "thumbv7em-none-eabi",
+            "cfg": ["target_arch=\"arm\""],
+            "metadata": { "description": null, "tier": 2, "host_tools": false, "std": false }
+        }
+    ]"#;
+    std::fs::write(&path, dump).unwrap();
+
+    let infos = super::load_rustc_info_dump(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let names = infos.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["x86_64-unknown-linux-gnu", "thumbv7em-none-eabi"]);
+
+    let (name, info) = infos.into_iter().next().unwrap();
+    assert_eq!(
+        info.target_cfgs,
+        vec![
+            ("target_arch".to_owned(), "\"x86_64\"".to_owned()),
+            ("target_env".to_owned(), "\"gnu\"".to_owned()),
+        ]
+    );
+
+    let doc = crate::render::render_target_md(
+        &TargetInfo {
+            name,
+            target_cfgs: info.target_cfgs,
+            metadata: info.metadata,
+            ..Default::default()
+        },
+        &Default::default(),
+    );
+    assert!(doc.starts_with(
+        "# x86_64-unknown-linux-gnu\n\n*64-bit Linux*\n\n**[Tier 1](../../target-tier-policy.md#tier-1-target-policy)**\n\n**std: Yes**\n\n**host tools: Yes**"
+    ));
+    assert!(doc.contains("- `target_arch` = `\"x86_64\"`"));
+}
+
+#[test]
+fn metadata_round_trip() {
+    for tier in [Some(1), Some(2), Some(3), None] {
+        let metadata = super::RustcTargetMetadata { tier, std: Some(true), ..Default::default() };
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(serde_json::from_str::<super::RustcTargetMetadata>(&json).unwrap(), metadata);
+    }
+}
+
+/// Writes a script answering like rustc with the target name as the arch and description,
+/// which logs its arguments next to it and fails for the target `broken`.
+#[cfg(unix)]
+fn fake_rustc(dir: &std::path::Path, version: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let rustc = dir.join("rustc");
+    let script = format!(
+        r#"#!/bin/sh
+echo "$*" >> "{log}"
+for target; do :; done
+if [ "$target" = broken ]; then
+    echo "error: unknown target triple 'broken'" >&2
+    exit 1
+fi
+case "$*" in
+    --version*) echo "{version}" ;;
+    *target-spec-json*) echo "{{\"metadata\": {{\"description\": \"$target\"}}}}" ;;
+    *) echo "target_arch=\"$target\"" ;;
+esac
+"#,
+        log = dir.join("log").display()
+    );
+    std::fs::write(&rustc, script).unwrap();
+    std::fs::set_permissions(&rustc, std::fs::Permissions::from_mode(0o755)).unwrap();
+    rustc
+}
+
+#[test]
+#[cfg(unix)]
+fn target_infos_keep_the_target_order() {
+    let dir = std::env::temp_dir().join(format!("target-docs-fake-rustc-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rustc = fake_rustc(&dir, "rustc 1.80.0");
+
+    let targets = (0..50).map(|i| format!("target{i}")).collect::<Vec<_>>();
+    let infos = super::rustc_target_infos(&rustc, &targets);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(infos.len(), targets.len());
+    for (target, info) in targets.iter().zip(infos) {
+        let info = info.unwrap();
+        assert_eq!(info.target_cfgs, [("target_arch".to_owned(), format!("\"{target}\""))]);
+        assert_eq!(info.metadata.description.as_ref(), Some(target));
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn cache_invalidated_by_rustc_version() {
+    let dir = std::env::temp_dir().join(format!("target-docs-rustc-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let cache = dir.join("cache.json");
+    let targets = ["a".to_owned(), "b".to_owned()];
+    let rustc_runs = |version: &str, targets: &[String]| {
+        let rustc = fake_rustc(&dir, version);
+        let _ = std::fs::remove_file(dir.join("log"));
+        let infos = super::cached_rustc_target_infos(&rustc, targets, &cache).unwrap();
+        let names =
+            infos.iter().map(|info| info.as_ref().unwrap().metadata.description.clone().unwrap());
+        assert_eq!(names.collect::<Vec<_>>(), targets);
+        std::fs::read_to_string(dir.join("log")).unwrap().lines().count()
+    };
+
+    // The version, then cfg and spec of every target not cached yet.
+    assert_eq!(rustc_runs("rustc 1.80.0", &targets), 5);
+    assert_eq!(rustc_runs("rustc 1.80.0", &targets), 1);
+    assert_eq!(rustc_runs("rustc 1.80.0", &["c".to_owned(), "a".to_owned()]), 3);
+    assert_eq!(rustc_runs("rustc 1.81.0", &targets), 5);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn failing_target() {
+    let dir = std::env::temp_dir().join(format!("target-docs-rustc-fails-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rustc = fake_rustc(&dir, "rustc 1.80.0");
+    let cache = dir.join("cache.json");
+    let targets = ["a".to_owned(), "broken".to_owned()];
+
+    let infos = super::rustc_target_infos(&rustc, &targets);
+    assert!(infos[0].is_ok());
+    let err = format!("{:#}", infos[1].as_ref().unwrap_err());
+    assert!(err.starts_with("`rustc --print cfg --target broken` failed: exit status: 1"), "{err}");
+    assert!(err.ends_with("error: unknown target triple 'broken'"), "{err}");
+
+    // Failures aren't cached, so the broken target is queried again.
+    super::cached_rustc_target_infos(&rustc, &targets, &cache).unwrap();
+    std::fs::remove_file(dir.join("log")).unwrap();
+    let infos = super::cached_rustc_target_infos(&rustc, &targets, &cache).unwrap();
+    let log = std::fs::read_to_string(dir.join("log")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(infos[0].is_ok() && infos[1].is_err());
+    assert_eq!(log, "--version --verbose\n--print cfg --target broken\n");
+}
@@ -0,0 +1,114 @@
+//! Running the whole pipeline on fixtures and comparing the output with golden files.
+//!
+//! The fixtures directory contains:
+//! - `target_info`: the target info markdown files
+//! - `rustc-info.json`: the rustc information, in the format of `--rustc-info`
+//! - `src`: the output directory before generating, with the static files
+//! - `expected`: the output directory after generating
+//!
+//! `SELF_TEST_BLESS=1` replaces the expected output with the actual output.
+
+use eyre::{bail, Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub fn run(fixtures: &Path) -> Result<()> {
+    if !fixtures.is_dir() {
+        bail!(
+            "the self-test fixtures {} don't exist, run it in the source checkout or pass the fixtures directory",
+            fixtures.display()
+        );
+    }
+    let output = std::env::temp_dir().join(format!(
+        "target-docs-self-test-{}-{}",
+        std::process::id(),
+        fixtures.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let result = run_in(fixtures, &output);
+    let _ = fs::remove_dir_all(&output);
+    result
+}
+
+fn run_in(fixtures: &Path, output: &Path) -> Result<()> {
+    let _ = fs::remove_dir_all(output);
+    copy_dir(&fixtures.join("src"), output).wrap_err("copying the output skeleton")?;
+
+    let args = [
+        "target-docs".to_owned(),
+        fixtures.join("target_info").display().to_string(),
+        output.display().to_string(),
+        "--rustc-info".to_owned(),
+        fixtures.join("rustc-info.json").display().to_string(),
+    ];
+    let args = crate::parse_args(args.into_iter())?;
+    crate::generate(&args, false, &mut crate::Progress::new(true, std::io::sink()), None)
+        .wrap_err("generating the docs of the fixtures")?;
+
+    let expected_dir = fixtures.join("expected");
+    if std::env::var("SELF_TEST_BLESS") == Ok("1".to_owned()) {
+        let _ = fs::remove_dir_all(&expected_dir);
+        return copy_dir(output, &expected_dir).wrap_err("blessing the expected output");
+    }
+
+    let actual = relative_files(output)?;
+    let expected = relative_files(&expected_dir)?;
+
+    let mut mismatches = Vec::new();
+    for file in &expected {
+        if !actual.contains(file) {
+            mismatches.push(format!("{}: not generated", file.display()));
+        }
+    }
+    for file in &actual {
+        if !expected.contains(file) {
+            mismatches.push(format!("{}: not expected", file.display()));
+        } else if fs::read(output.join(file))? != fs::read(expected_dir.join(file))? {
+            mismatches.push(format!("{}: content differs", file.display()));
+        }
+    }
+
+    if !mismatches.is_empty() {
+        bail!(
+            "the output differs from {}, run with SELF_TEST_BLESS=1 to update it:\n{}",
+            expected_dir.display(),
+            mismatches.join("\n")
+        );
+    }
+    Ok(())
+}
+
+pub fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    for file in relative_files(from)? {
+        let to = to.join(&file);
+        fs::create_dir_all(to.parent().unwrap_or(Path::new("")))?;
+        fs::copy(from.join(&file), &to)
+            .wrap_err_with(|| format!("copying {}", from.join(&file).display()))?;
+    }
+    Ok(())
+}
+
+/// All files in the directory and its subdirectories, relative to it and sorted.
+fn relative_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(relative_dir) = dirs.pop() {
+        let entries = fs::read_dir(dir.join(&relative_dir))
+            .wrap_err_with(|| format!("reading {}", dir.join(&relative_dir).display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let relative = relative_dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                dirs.push(relative);
+            } else {
+                files.push(relative);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests;
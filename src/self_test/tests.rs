@@ -0,0 +1,6 @@
+use std::path::Path;
+
+#[test]
+fn fixtures() {
+    super::run(&Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test")).unwrap();
+}
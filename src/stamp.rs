@@ -0,0 +1,74 @@
+//! Stamps in generated files: checksums at the end, to detect when they were edited by hand,
+//! and provenance comments at the start, recording what generated them.
+
+use eyre::{bail, Context, OptionExt, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STAMP_PREFIX: &str = "<!-- generated by target-docs, checksum: ";
+const STAMP_SUFFIX: &str = " -->\n";
+
+/// Appends a comment with the checksum of the content.
+pub fn add_stamp(content: &str) -> String {
+    format!("{content}{STAMP_PREFIX}{:016x}{STAMP_SUFFIX}", checksum(content))
+}
+
+/// Verifies that the content still matches the checksum in its stamp.
+pub fn verify_stamp(stamped: &str) -> Result<()> {
+    let (content, stamp) = stamped.rsplit_once(STAMP_PREFIX).ok_or_eyre("missing stamp")?;
+    let stamp = stamp.strip_suffix(STAMP_SUFFIX).ok_or_eyre("malformed stamp")?;
+    let expected = u64::from_str_radix(stamp, 16).map_err(|_| eyre::eyre!("malformed stamp"))?;
+
+    if checksum(content) != expected {
+        bail!("content does not match its checksum, the file was edited after it was generated");
+    }
+    Ok(())
+}
+
+/// A FNV-1a hash, which is stable across platforms and Rust versions.
+fn checksum(content: &str) -> u64 {
+    content
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+}
+
+/// The comment stating the versions of this tool and rustc and when the file was generated.
+/// `source_date_epoch` is the value of `SOURCE_DATE_EPOCH`, used instead of the current time
+/// to keep builds reproducible.
+pub fn provenance_comment(rustc_version: &str, source_date_epoch: Option<&str>) -> Result<String> {
+    let seconds = match source_date_epoch {
+        Some(epoch) => epoch.trim().parse::<u64>().wrap_err("invalid SOURCE_DATE_EPOCH")?,
+        None => SystemTime::now().duration_since(UNIX_EPOCH).wrap_err("system time")?.as_secs(),
+    };
+    Ok(format!(
+        "<!-- generated by target-docs {}, rustc: {rustc_version}, at: {} -->\n",
+        env!("CARGO_PKG_VERSION"),
+        format_utc(seconds)
+    ))
+}
+
+/// Formats seconds since the Unix epoch as an ISO 8601 UTC timestamp.
+pub fn format_utc(seconds: u64) -> String {
+    let (days, time) = (seconds / 86400, seconds % 86400);
+    // Converts days since the epoch to a civil date, see
+    // <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests;
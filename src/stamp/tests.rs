@@ -0,0 +1,29 @@
+#[test]
+fn round_trip() {
+    let content = "# x86_64-unknown-linux-gnu\n\n**Tier: 1**\n";
+    let stamped = super::add_stamp(content);
+
+    assert!(stamped.starts_with(content));
+    assert!(stamped.ends_with(" -->\n"));
+    super::verify_stamp(&stamped).unwrap();
+
+    let edited = stamped.replace("Tier: 1", "Tier: 2");
+    assert!(super::verify_stamp(&edited).is_err());
+    assert!(super::verify_stamp(content).is_err());
+}
+
+#[test]
+fn provenance_source_date_epoch() {
+    let comment = super::provenance_comment("rustc 1.80.0", Some("1700000000")).unwrap();
+    assert_eq!(
+        comment,
+        format!(
+            "<!-- generated by target-docs {}, rustc: rustc 1.80.0, at: 2023-11-14T22:13:20Z -->\n",
+            env!("CARGO_PKG_VERSION")
+        )
+    );
+
+    assert_eq!(super::format_utc(0), "1970-01-01T00:00:00Z");
+    assert_eq!(super::format_utc(951782400), "2000-02-29T00:00:00Z");
+    assert!(super::provenance_comment("rustc 1.80.0", Some("yesterday")).is_err());
+}
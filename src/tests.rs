@@ -0,0 +1,678 @@
+use crate::{
+    parse::{ParsedTargetInfoFile, SharedNotes, TriStateBool},
+    PageFilter, RustcTargetMetadata, TargetInfo,
+};
+
+#[test]
+fn page_filter_std() {
+    let target = |name: &str, std| TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata { std, ..Default::default() },
+        ..Default::default()
+    };
+    let targets = [
+        target("x86_64-unknown-linux-gnu", Some(true)),
+        target("thumbv7em-none-eabi", Some(false)),
+        target("cat-unknown-none", None),
+    ];
+    let filter = PageFilter { std: Some(TriStateBool::False), host: None };
+
+    let filtered = targets
+        .iter()
+        .filter(|target| filter.matches(target))
+        .map(|target| target.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(filter.std, Some(TriStateBool::False));
+    assert_eq!(filtered, vec!["thumbv7em-none-eabi"]);
+    assert!(targets.iter().all(|target| PageFilter::default().matches(target)));
+}
+
+#[test]
+fn quiet_progress() {
+    let mut quiet = crate::Progress::new(true, Vec::new());
+    quiet.message("Collecting rustc information");
+    assert!(quiet.out.is_none());
+
+    let mut loud = crate::Progress::new(false, Vec::new());
+    loud.message("Collecting rustc information");
+    assert_eq!(loud.out.unwrap(), b"Collecting rustc information\n");
+}
+
+#[test]
+fn explain_target() {
+    let linux = ParsedTargetInfoFile {
+        pattern: "*-linux-*".to_owned(),
+        maintainers: vec!["@penguin".to_owned()],
+        ..Default::default()
+    };
+    let x86_64_linux = ParsedTargetInfoFile {
+        pattern: "x86_64-unknown-linux-*".to_owned(),
+        sections: vec![("Testing".to_owned(), "CI.".to_owned())],
+        experimental: true,
+        toolchain_requirements: vec![crate::parse::ToolchainRequirement {
+            tool: "LLVM".to_owned(),
+            min_version: "17".to_owned(),
+        }],
+        ..Default::default()
+    };
+    let windows = ParsedTargetInfoFile { pattern: "*-windows-*".to_owned(), ..Default::default() };
+    let patterns = [linux, windows, x86_64_linux];
+
+    assert_eq!(
+        crate::explain_target(&patterns, "x86_64-unknown-linux-gnu"),
+        "patterns matching x86_64-unknown-linux-gnu, in resolution order:
+- `*-linux-*`
+  - maintainers: @penguin
+- `x86_64-unknown-linux-*`
+  - sections: Testing
+  - experimental
+  - LLVM toolchain requirement: 17
+"
+    );
+    assert_eq!(
+        crate::explain_target(&patterns, "wasm32-unknown-unknown"),
+        "no pattern matches wasm32-unknown-unknown\n"
+    );
+}
+
+#[test]
+fn sort_tier_then_name() {
+    let target = |name: &str, tier| TargetInfo {
+        name: name.to_owned(),
+        metadata: RustcTargetMetadata { tier, ..Default::default() },
+        ..Default::default()
+    };
+    let mut targets = vec![
+        target("x86_64-unknown-linux-gnu", Some(1)),
+        target("cat-unknown-none", None),
+        target("riscv64gc-unknown-linux-gnu", Some(2)),
+        target("aarch64-unknown-linux-gnu", Some(1)),
+        target("aarch64-unknown-fuchsia", Some(2)),
+    ];
+
+    crate::sort_targets(&mut targets, crate::SortOrder::TierThenName);
+
+    let names = targets.iter().map(|target| target.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "aarch64-unknown-linux-gnu",
+            "x86_64-unknown-linux-gnu",
+            "aarch64-unknown-fuchsia",
+            "riscv64gc-unknown-linux-gnu",
+            "cat-unknown-none",
+        ]
+    );
+}
+
+#[test]
+fn count_undocumented() {
+    let documented = TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        sections: vec![("Testing".to_owned(), "CI.".to_owned())],
+        ..Default::default()
+    };
+    let stubbed = |name: &str| TargetInfo {
+        name: name.to_owned(),
+        maintainers: vec!["@someone".to_owned()],
+        ..Default::default()
+    };
+    let targets = [documented, stubbed("cat-unknown-none"), stubbed("dog-unknown-none")];
+
+    assert_eq!(crate::count_undocumented(&targets), 2);
+}
+
+#[test]
+fn restrict_targets() {
+    let path =
+        std::env::temp_dir().join(format!("target-docs-targets-from-{}", std::process::id()));
+    let all_targets =
+        ["aarch64-unknown-linux-gnu", "thumbv7em-none-eabi", "x86_64-pc-windows-msvc"]
+            .map(ToOwned::to_owned)
+            .to_vec();
+
+    std::fs::write(&path, "x86_64-pc-windows-msvc\n\naarch64-unknown-linux-gnu\n").unwrap();
+    let targets = crate::restrict_targets(all_targets.clone(), &path).unwrap();
+    assert_eq!(targets, ["aarch64-unknown-linux-gnu", "x86_64-pc-windows-msvc"]);
+
+    std::fs::write(&path, "x86_64-pc-windows-msvc\ncat-unknown-none\n").unwrap();
+    let err = crate::restrict_targets(all_targets, &path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(err.to_string().ends_with("lists unknown targets: `cat-unknown-none`"));
+}
+
+#[test]
+fn commit_message() {
+    let changed = [
+        "src/platform-support/targets/x86_64-unknown-linux-gnu.md",
+        "src/platform-support/targets.md",
+        "src/platform-support/targets/cat-unknown-none.md",
+        "src/SUMMARY.md",
+    ]
+    .map(std::path::PathBuf::from);
+
+    assert_eq!(
+        crate::commit_message(&changed),
+        "Update generated target docs\n\nChanged targets:\n- x86_64-unknown-linux-gnu\n- cat-unknown-none\n"
+    );
+    assert_eq!(crate::commit_message(&changed[3..]), "Update generated target docs\n");
+}
+
+#[test]
+fn post_process_page() {
+    let args = ["target-docs", "target_infos", "src"].map(ToOwned::to_owned);
+    let args = crate::parse_args(args.into_iter()).unwrap();
+    let target = TargetInfo { name: "x86_64-unknown-linux-gnu".to_owned(), ..Default::default() };
+
+    let ran_for = std::cell::RefCell::new(Vec::new());
+    let uppercase_title: crate::PostProcess = Box::new(|target, content| {
+        ran_for.borrow_mut().push(target.to_owned());
+        let (title, rest) = content.split_once('\n').unwrap();
+        Ok(format!("{}\n{rest}", title.to_uppercase()))
+    });
+
+    let doc = crate::render_page(&target, &args, Some(&uppercase_title), None).unwrap();
+    assert!(doc.starts_with("# X86_64-UNKNOWN-LINUX-GNU\n\n**Tier: UNKNOWN**"));
+    assert_eq!(*ran_for.borrow(), ["x86_64-unknown-linux-gnu"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn post_process_hook() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-hook-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures, &dir).unwrap();
+
+    let args = [
+        "target-docs".to_owned(),
+        dir.join("target_info").display().to_string(),
+        dir.join("src").display().to_string(),
+        "--rustc-info".to_owned(),
+        dir.join("rustc-info.json").display().to_string(),
+        "--post-process-cmd".to_owned(),
+        "sed s/Tier/TIER/".to_owned(),
+    ];
+    let args = crate::parse_args(args.into_iter()).unwrap();
+    let hook: crate::PostProcess =
+        Box::new(|target, content| Ok(format!("<!-- {target} -->\n{content}")));
+    let result =
+        crate::generate(&args, false, &mut crate::Progress::new(true, std::io::sink()), Some(hook));
+    let page = dir.join("src/platform-support/targets/thumbv7em-none-eabi.md");
+    let page = std::fs::read_to_string(page).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    result.unwrap();
+    assert!(page.starts_with("<!-- thumbv7em-none-eabi -->\n# thumbv7em-none-eabi\n"));
+    assert!(page.contains("TIER"));
+}
+
+#[cfg(unix)]
+#[test]
+fn post_process_cmd_with_arguments() {
+    let page = "# x86_64-unknown-linux-gnu\n\n**Tier: unknown**\n".to_owned();
+    let doc = crate::run_post_process_cmd("sed s/Tier/TIER/", "x86_64-unknown-linux-gnu", page);
+    assert_eq!(doc.unwrap(), "# x86_64-unknown-linux-gnu\n\n**TIER: unknown**\n");
+
+    let doc =
+        crate::run_post_process_cmd("cat; echo \"$1\"", "x86_64-unknown-linux-gnu", "".into());
+    assert_eq!(doc.unwrap(), "x86_64-unknown-linux-gnu\n");
+}
+
+#[test]
+fn verify_stamps() {
+    let dir = std::env::temp_dir().join(format!("target-docs-verify-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("images")).unwrap();
+    let page = crate::stamp::add_stamp("# x86_64-unknown-linux-gnu\n");
+    std::fs::write(dir.join("x86_64-unknown-linux-gnu.md"), &page).unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a page").unwrap();
+
+    let verified = crate::verify_stamps(&dir, "md");
+    std::fs::write(dir.join("cat-unknown-none.md"), "# cat-unknown-none\n").unwrap();
+    let unstamped = crate::verify_stamps(&dir, "md").unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    verified.unwrap();
+    assert_eq!(
+        unstamped.to_string(),
+        format!(
+            "target pages failed stamp verification:\n{}: missing stamp",
+            dir.join("cat-unknown-none.md").display()
+        )
+    );
+}
+
+#[test]
+fn prune_target_pages() {
+    let dir = std::env::temp_dir().join(format!("target-docs-prune-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in [
+        "x86_64-unknown-linux-gnu.md",
+        "x86_64-linux-gnu.md",
+        "removed-unknown-none.md",
+        "notes.txt",
+    ] {
+        std::fs::write(dir.join(file), "").unwrap();
+    }
+    let targets = [TargetInfo {
+        name: "x86_64-unknown-linux-gnu".to_owned(),
+        aliases: vec!["x86_64-linux-gnu".to_owned()],
+        ..Default::default()
+    }];
+
+    let mut check_writer = crate::OutputWriter::new(true);
+    crate::prune_target_pages(&mut check_writer, &dir, &targets, "md").unwrap();
+    assert_eq!(check_writer.changed(), [dir.join("removed-unknown-none.md")]);
+    assert!(dir.join("removed-unknown-none.md").exists());
+
+    let mut writer = crate::OutputWriter::new(false);
+    crate::prune_target_pages(&mut writer, &dir, &targets, "md").unwrap();
+    let mut remaining = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    remaining.sort();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(remaining, ["notes.txt", "x86_64-linux-gnu.md", "x86_64-unknown-linux-gnu.md"]);
+}
+
+#[test]
+fn dump_cfg() {
+    let path =
+        std::env::temp_dir().join(format!("target-docs-dump-cfg-{}.json", std::process::id()));
+    let dump = r#"[{
+        "target": "x86_64-unknown-linux-gnu",
+        "cfg": ["target_os=\"linux\"", "unix", "target_arch=\"x86_64\"", "target_env=\"gnu\""],
+        "metadata": { "description": null, "tier": 1, "host_tools": true, "std": true }
+    }]"#;
+    std::fs::write(&path, dump).unwrap();
+
+    let cfg = crate::dump_cfg("x86_64-unknown-linux-gnu", Some(&path), None).unwrap();
+    let unknown = crate::dump_cfg("cat-unknown-none", Some(&path), None);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(cfg, "target_arch=\"x86_64\"\ntarget_env=\"gnu\"\ntarget_os=\"linux\"\n");
+    assert!(unknown.is_err());
+}
+
+#[test]
+fn find_rustc() {
+    let dir = std::env::temp_dir().join(format!("target-docs-find-rustc-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rustc = dir.join(format!("rustc{}", std::env::consts::EXE_SUFFIX));
+    std::fs::write(&rustc, "").unwrap();
+
+    let set = crate::find_rustc(Some("/opt/rust/bin/rustc".into()), Some(dir.clone().into()));
+    let on_path = crate::find_rustc(None, Some(dir.clone().into()));
+    let missing = crate::find_rustc(None, Some("".into()));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(set.unwrap(), std::path::Path::new("/opt/rust/bin/rustc"));
+    assert_eq!(on_path.unwrap(), rustc);
+    assert_eq!(
+        missing.unwrap_err().to_string(),
+        "`RUSTC` is not set and there is no `rustc` on the `PATH`, \
+        pass `--rustc` with the rustc to document or `--rustc-info`"
+    );
+}
+
+#[test]
+fn resolve_target() {
+    let pattern = |pattern: &str, maintainer: &str| ParsedTargetInfoFile {
+        pattern: pattern.to_owned(),
+        maintainers: vec![maintainer.to_owned()],
+        stabilized_in: Some("1.74".to_owned()),
+        ..Default::default()
+    };
+    let patterns = [pattern("*-linux-gnu", "@penguin"), pattern("*-apple-*", "@apple")];
+
+    let info = crate::resolve_target(&patterns, "x86_64-unknown-linux-gnu").unwrap();
+    assert_eq!(info.name, "x86_64-unknown-linux-gnu");
+    assert_eq!(info.maintainers, ["@penguin"]);
+    assert_eq!(info.stabilized_in.as_deref(), Some("1.74"));
+
+    let patterns = [pattern("*-linux-gnu", "@penguin"), pattern("x86_64-*", "@x86")];
+    let err = crate::resolve_target(&patterns, "x86_64-unknown-linux-gnu").err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "target x86_64-unknown-linux-gnu inherits `stabilized_in` from multiple patterns, \
+        `*-linux-gnu` and `x86_64-*`, create a more specific pattern and add it there"
+    );
+
+    let section = |pattern: &str| ParsedTargetInfoFile {
+        pattern: pattern.to_owned(),
+        sections: vec![("Testing".to_owned(), "Pet the cat.".to_owned())],
+        ..Default::default()
+    };
+    let patterns = [section("*-linux-gnu"), section("*-unknown-*"), section("x86_64-*")];
+    let err = crate::resolve_target(&patterns, "x86_64-unknown-linux-gnu").err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "target x86_64-unknown-linux-gnu inherits the section Testing from multiple patterns, \
+        `*-linux-gnu` and `*-unknown-*`, create a more specific pattern and add it there"
+    );
+
+    // The minimum macOS version and the required macOS SDK are different fields.
+    let min_macos = ParsedTargetInfoFile {
+        pattern: "*-apple-darwin".to_owned(),
+        min_versions: vec![("macOS".to_owned(), "10.12".to_owned())],
+        ..Default::default()
+    };
+    let macos_sdk = ParsedTargetInfoFile {
+        pattern: "aarch64-apple-*".to_owned(),
+        toolchain_requirements: vec![crate::parse::ToolchainRequirement {
+            tool: "macOS".to_owned(),
+            min_version: "11".to_owned(),
+        }],
+        ..Default::default()
+    };
+    let info = crate::resolve_target(&[min_macos, macos_sdk], "aarch64-apple-darwin").unwrap();
+    assert_eq!(info.min_versions, [("macOS".to_owned(), "10.12".to_owned())]);
+    assert_eq!(info.toolchain_requirements.len(), 1);
+}
+
+#[test]
+fn resolve_target_deduplicates_maintainers() {
+    let pattern = |pattern: &str, maintainers: &[&str]| ParsedTargetInfoFile {
+        pattern: pattern.to_owned(),
+        maintainers: maintainers.iter().map(|maintainer| maintainer.to_string()).collect(),
+        ..Default::default()
+    };
+    let patterns = [
+        pattern("*-linux-*", &["@penguin", "@tux"]),
+        pattern("x86_64-unknown-linux-gnu", &["@x86", "@penguin ", "@Tux"]),
+    ];
+
+    let info = crate::resolve_target(&patterns, "x86_64-unknown-linux-gnu").unwrap();
+    assert_eq!(info.maintainers, ["@penguin", "@tux", "@x86", "@Tux"]);
+}
+
+#[test]
+fn check_summary_json() {
+    let warnings = ["the targets reference each other in a `see_also` cycle".to_owned()];
+    let changed = Ok(vec![std::path::PathBuf::from("src/SUMMARY.md")]);
+    let summary: serde_json::Value =
+        serde_json::from_str(&crate::check_summary_json(&changed, &warnings)).unwrap();
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "changed_files": ["src/SUMMARY.md"],
+            "errors": [],
+            "warnings": ["the targets reference each other in a `see_also` cycle"],
+        })
+    );
+
+    let failed = Err(eyre::eyre!("target pattern `cat-*` was never used").wrap_err("checking"));
+    let summary: serde_json::Value =
+        serde_json::from_str(&crate::check_summary_json(&failed, &[])).unwrap();
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "changed_files": [],
+            "errors": ["checking: target pattern `cat-*` was never used"],
+            "warnings": [],
+        })
+    );
+}
+
+#[test]
+fn shared_notes() {
+    let target = |name: &str, family: &str| TargetInfo {
+        name: name.to_owned(),
+        sections: vec![("Testing".to_owned(), "Tested in CI.".to_owned())],
+        target_cfgs: vec![("target_family".to_owned(), format!("\"{family}\""))],
+        ..Default::default()
+    };
+    let mut targets = [
+        target("x86_64-pc-windows-msvc", "windows"),
+        target("x86_64-pc-windows-gnu", "windows"),
+        target("x86_64-unknown-linux-gnu", "unix"),
+    ];
+    let shared_notes = [SharedNotes {
+        family: "windows".to_owned(),
+        sections: vec![
+            ("Overview".to_owned(), "Windows 10 or later.".to_owned()),
+            ("Testing".to_owned(), "Tested on Windows Server.".to_owned()),
+        ],
+    }];
+
+    crate::apply_shared_notes(&mut targets, &shared_notes);
+    let options = crate::render::RenderOptions::default();
+    for windows in &targets[..2] {
+        let doc = crate::render::render_target_md(windows, &options);
+        assert!(doc.contains(
+            "## Overview\n*Shared by all `windows` targets:*\n\nWindows 10 or later.\n\n"
+        ));
+        assert!(doc.contains(
+            "## Testing\nTested in CI.\n\n*Shared by all `windows` targets:*\n\nTested on Windows Server.\n\n"
+        ));
+    }
+    let linux = crate::render::render_target_md(&targets[2], &options);
+    assert!(!linux.contains("windows"));
+}
+
+#[test]
+fn dangling_links_fail_the_run() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-dangling-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures, &dir).unwrap();
+    let info = dir.join("target_info").join("thumbv7em-none-eabi.md");
+    let content = std::fs::read_to_string(&info).unwrap();
+    std::fs::write(&info, content.replace("---\n## ", "see_also: [cat-unknown-none]\n---\n## "))
+        .unwrap();
+
+    let args = [
+        "target-docs".to_owned(),
+        dir.join("target_info").display().to_string(),
+        dir.join("src").display().to_string(),
+        "--rustc-info".to_owned(),
+        dir.join("rustc-info.json").display().to_string(),
+    ];
+    let args = crate::parse_args(args.into_iter()).unwrap();
+    let result =
+        crate::generate(&args, false, &mut crate::Progress::new(true, std::io::sink()), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let page = dir.join("src/platform-support/targets/thumbv7em-none-eabi.md");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        format!(
+            "the output links to files that don't exist:\n{}: `../targets/cat-unknown-none.md`",
+            page.display()
+        )
+    );
+}
+
+#[test]
+fn page_filter_keeps_links_to_other_targets() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-filter-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures.join("src"), &dir).unwrap();
+
+    let args = [
+        "target-docs".to_owned(),
+        fixtures.join("target_info").display().to_string(),
+        dir.display().to_string(),
+        "--rustc-info".to_owned(),
+        fixtures.join("rustc-info.json").display().to_string(),
+        "--filter-std".to_owned(),
+        "false".to_owned(),
+    ];
+    let args = crate::parse_args(args.into_iter()).unwrap();
+    let result =
+        crate::generate(&args, false, &mut crate::Progress::new(true, std::io::sink()), None);
+    let targets = dir.join("platform-support/targets");
+    let pages = std::fs::read_dir(&targets).map(|pages| pages.count());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    result.unwrap();
+    assert_eq!(pages.unwrap(), 1);
+}
+
+#[test]
+fn only_one_target() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-only-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures.join("src"), &dir).unwrap();
+
+    let args = |only: &str| {
+        let args = [
+            "target-docs".to_owned(),
+            fixtures.join("target_info").display().to_string(),
+            dir.display().to_string(),
+            "--rustc-info".to_owned(),
+            fixtures.join("rustc-info.json").display().to_string(),
+            "--only".to_owned(),
+            only.to_owned(),
+        ];
+        crate::parse_args(args.into_iter()).unwrap()
+    };
+    let mut progress = crate::Progress::new(true, std::io::sink());
+    let changed = crate::generate(&args("thumbv7em-none-eabi"), false, &mut progress, None);
+    let typo =
+        crate::generate(&args("thumbv7m-none-eabi"), false, &mut progress, None).unwrap_err();
+    let unknown =
+        crate::generate(&args("cat-unknown-none"), false, &mut progress, None).unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // The static files still have their markers, they weren't filled in.
+    assert_eq!(changed.unwrap(), [dir.join("platform-support/targets/thumbv7em-none-eabi.md")]);
+    assert_eq!(
+        typo.to_string(),
+        "unknown target `thumbv7m-none-eabi`, did you mean `thumbv7em-none-eabi`?"
+    );
+    assert_eq!(unknown.to_string(), "unknown target `cat-unknown-none`");
+}
+
+#[test]
+fn edit_distance() {
+    assert_eq!(crate::edit_distance("", "abc"), 3);
+    assert_eq!(crate::edit_distance("kitten", "sitting"), 3);
+    assert_eq!(crate::edit_distance("x86_64-unknown-linux-gnu", "x86_64-unknown-linux-gnu"), 0);
+    assert_eq!(crate::edit_distance("thumbv7em-none-eabi", "thumbv7m-none-eabi"), 1);
+}
+
+#[test]
+fn cli() {
+    let parse = |args: &[&str]| {
+        let args = ["target-docs"].iter().chain(args).map(|arg| arg.to_string());
+        crate::parse_args(args.collect::<Vec<_>>().into_iter())
+    };
+
+    let args = parse(&["target_infos", "src", "--check", "--rustc", "/opt/rustc"]).unwrap();
+    assert_eq!((args.input_dir.as_str(), args.output_src.as_str()), ("target_infos", "src"));
+    assert!(args.check);
+    assert_eq!(args.rustc.as_deref(), Some(std::path::Path::new("/opt/rustc")));
+    let self_test = parse(&["--self-test"]).unwrap().self_test;
+    assert_eq!(self_test.as_deref(), Some(std::path::Path::new("self-test")));
+    let self_test = parse(&["--self-test", "/src/target-docs/self-test"]).unwrap().self_test;
+    assert_eq!(self_test.as_deref(), Some(std::path::Path::new("/src/target-docs/self-test")));
+
+    let named = parse(&["--output-src", "src", "--input-dir", "target_infos"]).unwrap();
+    assert_eq!((named.input_dir.as_str(), named.output_src.as_str()), ("target_infos", "src"));
+    let mixed = parse(&["target_infos", "--output-src", "src"]).unwrap();
+    assert_eq!((mixed.input_dir.as_str(), mixed.output_src.as_str()), ("target_infos", "src"));
+    assert!(parse(&["target_infos", "src", "--input-dir", "other"]).is_err());
+
+    let missing = parse(&["target_infos"]).err().unwrap();
+    assert!(missing.to_string().contains("<OUTPUT_SRC_DIR>"));
+    assert!(parse(&["target_infos", "src", "--nope"]).is_err());
+    let err = parse(&["target_infos", "src", "--max-notes-len", "x"]).err().unwrap();
+    assert!(err.to_string().contains("invalid value 'x' for '--max-notes-len <LENGTH>'"));
+    let args = parse(&["target_infos", "src", "--sort", "tier-then-name", "--filter-std", "false"]);
+    let args = args.unwrap();
+    assert_eq!(args.sort, crate::SortOrder::TierThenName);
+    assert_eq!(args.page_filter.std, Some(TriStateBool::False));
+    assert_eq!(args.check_format, crate::CheckFormat::Human);
+    assert!(parse(&["target_infos", "src", "--sort", "tier"]).is_err());
+}
+
+#[test]
+fn output_independent_of_rustc_order() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-order-{}", std::process::id()));
+    let rustc_info = std::fs::read_to_string(fixtures.join("rustc-info.json")).unwrap();
+    let mut targets = serde_json::from_str::<Vec<serde_json::Value>>(&rustc_info).unwrap();
+
+    let mut outputs = Vec::new();
+    for order in ["rustc", "reversed"] {
+        let output = dir.join(order);
+        crate::self_test::copy_dir(&fixtures.join("src"), &output).unwrap();
+        std::fs::write(dir.join("rustc-info.json"), serde_json::to_string(&targets).unwrap())
+            .unwrap();
+        let args = [
+            "target-docs".to_owned(),
+            fixtures.join("target_info").display().to_string(),
+            output.display().to_string(),
+            "--rustc-info".to_owned(),
+            dir.join("rustc-info.json").display().to_string(),
+        ];
+        let args = crate::parse_args(args.into_iter()).unwrap();
+        let changed =
+            crate::generate(&args, false, &mut crate::Progress::new(true, std::io::sink()), None);
+        let files = changed.unwrap().into_iter().map(|file| {
+            (file.strip_prefix(&output).unwrap().to_owned(), std::fs::read(&file).unwrap())
+        });
+        outputs.push(files.collect::<std::collections::BTreeMap<_, _>>());
+        targets.reverse();
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(outputs[0].len() > 3);
+    assert!(outputs[0] == outputs[1]);
+}
+
+#[test]
+fn unmaintained_targets() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-unmaintained-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures, &dir).unwrap();
+    let info = dir.join("target_info").join("thumbv7em-none-eabi.md");
+    let content = std::fs::read_to_string(&info).unwrap();
+    std::fs::write(&info, content.replace("maintainers: [\"@embedded\"]\n", "")).unwrap();
+
+    let args = [
+        "target-docs".to_owned(),
+        dir.join("target_info").display().to_string(),
+        dir.join("src").display().to_string(),
+        "--rustc-info".to_owned(),
+        dir.join("rustc-info.json").display().to_string(),
+    ];
+    let args = crate::parse_args(args.into_iter()).unwrap();
+    let mut progress = crate::Progress::new(true, std::io::sink());
+    let checked = crate::generate(&args, true, &mut progress, None).map(|_| ());
+    let generated = crate::generate(&args, false, &mut progress, None);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let message = "targets without maintainers:\ntier 2: thumbv7em-none-eabi";
+    assert_eq!(checked.unwrap_err().to_string(), message);
+    assert!(generated.is_ok());
+    assert_eq!(progress.warnings, [message]);
+}
+
+#[test]
+fn unmatched_targets() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("self-test");
+    let dir = std::env::temp_dir().join(format!("target-docs-unmatched-{}", std::process::id()));
+    crate::self_test::copy_dir(&fixtures, &dir).unwrap();
+    std::fs::remove_file(dir.join("target_info").join("thumbv7em-none-eabi.md")).unwrap();
+
+    let args = [
+        "target-docs".to_owned(),
+        dir.join("target_info").display().to_string(),
+        dir.join("src").display().to_string(),
+        "--rustc-info".to_owned(),
+        dir.join("rustc-info.json").display().to_string(),
+    ];
+    let args = crate::parse_args(args.into_iter()).unwrap();
+    let mut progress = crate::Progress::new(true, std::io::sink());
+    let checked = crate::generate(&args, true, &mut progress, None).map(|_| ());
+    let generated = crate::generate(&args, false, &mut progress, None);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let message = "targets not matched by any target pattern:\nthumbv7em-none-eabi";
+    assert_eq!(checked.unwrap_err().to_string(), message);
+    assert!(generated.is_ok());
+    assert_eq!(progress.warnings[0], message);
+}
@@ -0,0 +1,88 @@
+//! Regenerating the docs whenever the input files change, by polling them.
+
+use eyre::{Context, Result};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// How often the files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+/// How long the files have to stay unchanged after a change, so saving many files at once
+/// or editors writing a file in multiple steps only regenerate once.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The modification time and size of every file in the watched directory.
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+pub struct Watcher {
+    dir: PathBuf,
+    snapshot: Snapshot,
+    /// When the files last changed, if the change wasn't reported yet.
+    changed_at: Option<Instant>,
+}
+
+impl Watcher {
+    pub fn new(dir: &Path) -> Result<Self> {
+        Ok(Self { dir: dir.to_owned(), snapshot: snapshot(dir)?, changed_at: None })
+    }
+
+    /// Returns whether the files changed and then stayed unchanged for `debounce`.
+    pub fn poll(&mut self, debounce: Duration) -> Result<bool> {
+        let current = snapshot(&self.dir)?;
+        if current != self.snapshot {
+            self.snapshot = current;
+            self.changed_at = Some(Instant::now());
+            return Ok(false);
+        }
+        match self.changed_at {
+            Some(changed_at) if changed_at.elapsed() >= debounce => {
+                self.changed_at = None;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Runs `generate` now and after every change of the files in `dir`, until the process is killed.
+/// `generate` returns how many output files changed.
+pub fn watch(dir: &Path, mut generate: impl FnMut() -> Result<usize>) -> Result<()> {
+    let mut watcher = Watcher::new(dir).wrap_err("watching the input files")?;
+    loop {
+        // Errors are the inputs being edited, they are reported and fixed by the next change.
+        let status = match generate() {
+            Ok(changed) => format!("regenerated, {changed} output files changed"),
+            Err(err) => format!("failed: {err:?}"),
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        eprintln!("[{}] {status}", crate::stamp::format_utc(now.as_secs()));
+
+        while !watcher.poll(DEBOUNCE)? {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+fn snapshot(dir: &Path) -> Result<Snapshot> {
+    let mut snapshot = Snapshot::new();
+    let mut dirs = vec![dir.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        let entries = fs::read_dir(&dir).wrap_err_with(|| format!("reading {}", dir.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                snapshot.insert(entry.path(), (metadata.modified()?, metadata.len()));
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+#[test]
+fn change_triggers_regeneration() {
+    let dir = std::env::temp_dir().join(format!("target-docs-watch-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("_shared")).unwrap();
+    std::fs::write(dir.join("x86_64-unknown-linux-gnu.md"), "---\n---\n").unwrap();
+
+    let mut watcher = super::Watcher::new(&dir).unwrap();
+    assert!(!watcher.poll(Duration::ZERO).unwrap());
+
+    std::fs::write(dir.join("_shared").join("windows.md"), "## Overview\n").unwrap();
+    // The first poll sees the change, the next one after the debounce reports it.
+    assert!(!watcher.poll(Duration::ZERO).unwrap());
+    assert!(watcher.poll(Duration::ZERO).unwrap());
+    assert!(!watcher.poll(Duration::ZERO).unwrap());
+
+    std::fs::write(dir.join("x86_64-unknown-linux-gnu.md"), "---\nexperimental: true\n---\n")
+        .unwrap();
+    assert!(!watcher.poll(Duration::from_secs(3600)).unwrap());
+    assert!(!watcher.poll(Duration::from_secs(3600)).unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}